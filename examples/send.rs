@@ -21,5 +21,5 @@ fn main() {
     let mock = MockA {
         foo: Mock::default(),
     };
-    let _ = Box::new(mock) as Box<A + Send>;
+    let _ = Box::new(mock) as Box<dyn A + Send>;
 }
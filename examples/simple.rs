@@ -26,7 +26,7 @@ impl<T: Dependency> Consumer<T> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct MockDependency {
     pub greet: Mock<String, ()>,
 }
@@ -37,23 +37,19 @@ impl Dependency for MockDependency {
     }
 }
 
-impl Default for MockDependency {
-    fn default() -> Self {
-        MockDependency {
-            greet: Mock::default(),
-        }
-    }
-}
-
 fn main() {
     let mock = MockDependency::default();
     let consumer = Consumer::new(&mock);
 
     consumer.greet_everyone(vec!["Fido", "Spot", "Princess"]);
 
-    assert_eq!(mock.greet.num_calls(), 3);
+    // Call tracking is compiled out by the `disabled` feature
+    #[cfg(not(feature = "disabled"))]
+    {
+        assert_eq!(mock.greet.num_calls(), 3);
 
-    assert!(mock.greet.called_with("Fido"));
-    assert!(mock.greet.called_with("Spot"));
-    assert!(mock.greet.called_with("Princess"));
+        assert!(mock.greet.called_with("Fido"));
+        assert!(mock.greet.called_with("Spot"));
+        assert!(mock.greet.called_with("Princess"));
+    }
 }
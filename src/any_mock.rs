@@ -0,0 +1,244 @@
+use std::any::Any;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+
+use mock::Mock;
+#[cfg(not(feature = "disabled"))]
+use verification_error::VerificationError;
+
+type AnyArgs = Box<dyn Any + Send + Sync>;
+type AnyClosure = Box<dyn Fn(&(dyn Any + Send + Sync)) -> Box<dyn Any + Send> + Send + Sync>;
+
+/// The arguments of a call to an `AnyMock`, as kept in its call history.
+///
+/// Arguments can't be compared or printed without knowing their type, so a
+/// record only equals itself and is shown as `Any { .. }`.
+#[derive(Clone)]
+struct SharedArgs(Arc<dyn Any + Send + Sync>);
+
+impl PartialEq for SharedArgs {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for SharedArgs {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("Any { .. }")
+    }
+}
+
+/// A dynamically typed mock, with arguments and return values passed as
+/// `Box<dyn Any>`.
+///
+/// `AnyMock` gives up the type safety of `Mock` in exchange for being able to
+/// back methods whose payload type varies, such as an object-safe message bus,
+/// or for quick prototyping. Arguments are downcast when they are inspected.
+///
+/// The call history is kept by a `Mock`, so that it behaves the same way,
+/// including under the `disabled` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::any::Any;
+///
+/// use pseudo::AnyMock;
+///
+/// trait Bus {
+///     fn request(&self, message: Box<dyn Any + Send + Sync>) -> Box<dyn Any + Send>;
+/// }
+///
+/// struct MockBus {
+///     request: AnyMock,
+/// }
+///
+/// impl Bus for MockBus {
+///     fn request(&self, message: Box<dyn Any + Send + Sync>) -> Box<dyn Any + Send> {
+///         self.request.call(message)
+///     }
+/// }
+///
+/// let bus = MockBus { request: AnyMock::new() };
+/// bus.request.return_value(200u16);
+///
+/// let response = bus.request(Box::new("ping"));
+///
+/// assert_eq!(response.downcast_ref::<u16>(), Some(&200));
+/// # #[cfg(not(feature = "disabled"))]
+/// assert!(bus.request.called_with("ping"));
+/// # #[cfg(not(feature = "disabled"))]
+/// assert!(!bus.request.called_with(42u64));
+/// ```
+#[derive(Clone)]
+pub struct AnyMock {
+    mock_closure: Arc<RwLock<AnyClosure>>,
+    history: Mock<SharedArgs, ()>,
+}
+
+impl AnyMock {
+    /// Creates a new `AnyMock` that will return `()`.
+    pub fn new() -> Self {
+        Self::with_closure(Box::new(|_| Box::new(())))
+    }
+
+    pub(crate) fn with_closure(mock_fn: AnyClosure) -> Self {
+        AnyMock {
+            mock_closure: Arc::new(RwLock::new(mock_fn)),
+            history: Mock::default(),
+        }
+    }
+
+    /// Use the `AnyMock` to return a value, keeping track of the arguments
+    /// used.
+    pub fn call(&self, args: AnyArgs) -> Box<dyn Any + Send> {
+        let args: Arc<dyn Any + Send + Sync> = Arc::from(args);
+        self.history.call(SharedArgs(args.clone()));
+        (*self.mock_closure.read().unwrap())(&*args)
+    }
+
+    /// Return a clone of `return_value` from `AnyMock::call`.
+    pub fn return_value<R>(&self, return_value: R)
+    where
+        R: Any + Clone + Send + Sync,
+    {
+        self.use_closure(Box::new(move |_| Box::new(return_value.clone())))
+    }
+
+    /// Specify a closure to determine the `AnyMock`'s return value based on
+    /// the arguments provided to `AnyMock::call`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::AnyMock;
+    ///
+    /// let mock = AnyMock::new();
+    /// mock.use_closure(Box::new(|args| match args.downcast_ref::<&str>() {
+    ///     Some(s) => Box::new(s.len()),
+    ///     None => Box::new(0usize),
+    /// }));
+    ///
+    /// assert_eq!(mock.call(Box::new("four")).downcast_ref::<usize>(), Some(&4));
+    /// assert_eq!(mock.call(Box::new(1.5f64)).downcast_ref::<usize>(), Some(&0));
+    /// ```
+    pub fn use_closure(&self, mock_fn: AnyClosure) {
+        *self.mock_closure.write().unwrap() = mock_fn
+    }
+
+    /// Identify the `AnyMock` by `name` in verification errors.
+    #[cfg(not(feature = "disabled"))]
+    pub fn set_name<T: Into<String>>(&self, name: T) {
+        self.history.set_name(name)
+    }
+
+    /// Expect `AnyMock::call` to be called exactly `n` times, as checked by
+    /// `AnyMock::verify`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::AnyMock;
+    ///
+    /// let mock = AnyMock::new();
+    /// mock.set_name("publish");
+    /// mock.expect_calls(2);
+    ///
+    /// mock.call(Box::new("a"));
+    ///
+    /// assert_eq!(mock.verify().unwrap_err().mock_name, Some("publish".to_string()));
+    ///
+    /// mock.call(Box::new(2u8));
+    ///
+    /// assert!(mock.verify().is_ok());
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn expect_calls(&self, n: usize) {
+        self.history.expect_calls(n)
+    }
+
+    /// Returns an error unless every expectation registered on the `AnyMock`
+    /// has been met.
+    #[cfg(not(feature = "disabled"))]
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        self.history.verify()
+    }
+
+    /// Returns true if `AnyMock::call` has been called.
+    #[cfg(not(feature = "disabled"))]
+    pub fn called(&self) -> bool {
+        self.history.called()
+    }
+
+    /// Returns the number of times `AnyMock::call` has been called.
+    #[cfg(not(feature = "disabled"))]
+    pub fn num_calls(&self) -> usize {
+        self.history.num_calls()
+    }
+
+    /// Returns the arguments of type `T` used for `AnyMock::call`, in order
+    /// from first to last. Arguments of other types are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::AnyMock;
+    ///
+    /// let mock = AnyMock::new();
+    /// mock.call(Box::new("first"));
+    /// mock.call(Box::new(2u8));
+    /// mock.call(Box::new("third"));
+    ///
+    /// assert_eq!(mock.num_calls(), 3);
+    /// assert_eq!(mock.calls_of::<&str>(), vec!["first", "third"]);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls_of<T>(&self) -> Vec<T>
+    where
+        T: Any + Clone,
+    {
+        self.history
+            .calls()
+            .iter()
+            .filter_map(|args| args.0.downcast_ref::<T>())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns true if the specified argument has been used for
+    /// `AnyMock::call`.
+    #[cfg(not(feature = "disabled"))]
+    pub fn called_with<T>(&self, args: T) -> bool
+    where
+        T: Any + PartialEq,
+    {
+        self.history
+            .calls()
+            .iter()
+            .any(|call| call.0.downcast_ref::<T>() == Some(&args))
+    }
+
+    /// Reset the call history for the `AnyMock`.
+    #[cfg(not(feature = "disabled"))]
+    pub fn reset_calls(&self) {
+        self.history.reset_calls()
+    }
+}
+
+impl Default for AnyMock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for AnyMock {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("AnyMock");
+
+        #[cfg(not(feature = "disabled"))]
+        debug.field("num_calls", &self.num_calls());
+
+        debug.finish()
+    }
+}
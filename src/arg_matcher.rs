@@ -0,0 +1,85 @@
+/// Matches the arguments of a call, or one position of a tuple of arguments.
+///
+/// Tuples of `ArgMatcher`s match tuples of arguments position by position,
+/// so multi-argument `Mock`s can be checked without pinning down every
+/// argument. Closures that take a reference to the argument are also
+/// `ArgMatcher`s.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{any, eq, Mock};
+///
+/// let log = Mock::<(&str, u64), ()>::default();
+/// log.call(("connected", 1_536_000_000));
+///
+/// assert!(log.called_with_args((eq("connected"), any())));
+/// assert!(log.called_with_args((any(), |t: &u64| *t > 0)));
+/// assert!(!log.called_with_args((eq("disconnected"), any())));
+/// ```
+pub trait ArgMatcher<T> {
+    /// Returns true if `args` satisfies the matcher.
+    fn matches(&self, args: &T) -> bool;
+}
+
+/// Matches any argument. See `any`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Anything;
+
+/// Matches arguments equal to a value. See `eq`.
+#[derive(Clone, Copy, Debug)]
+pub struct Equals<T>(T);
+
+/// Returns an `ArgMatcher` that matches any argument.
+pub fn any() -> Anything {
+    Anything
+}
+
+/// Returns an `ArgMatcher` that matches arguments equal to `value`.
+pub fn eq<T>(value: T) -> Equals<T> {
+    Equals(value)
+}
+
+impl<T> ArgMatcher<T> for Anything {
+    fn matches(&self, _: &T) -> bool {
+        true
+    }
+}
+
+impl<T, U> ArgMatcher<T> for Equals<U>
+where
+    T: PartialEq<U>,
+{
+    fn matches(&self, args: &T) -> bool {
+        *args == self.0
+    }
+}
+
+impl<T, F> ArgMatcher<T> for F
+where
+    F: Fn(&T) -> bool,
+{
+    fn matches(&self, args: &T) -> bool {
+        self(args)
+    }
+}
+
+macro_rules! tuple_matcher {
+    ($($arg:ident $matcher:ident $index:tt),+) => {
+        impl<$($arg, $matcher),+> ArgMatcher<($($arg,)+)> for ($($matcher,)+)
+        where
+            $($matcher: ArgMatcher<$arg>),+
+        {
+            fn matches(&self, args: &($($arg,)+)) -> bool {
+                $(self.$index.matches(&args.$index))&&+
+            }
+        }
+    };
+}
+
+tuple_matcher!(A0 M0 0);
+tuple_matcher!(A0 M0 0, A1 M1 1);
+tuple_matcher!(A0 M0 0, A1 M1 1, A2 M2 2);
+tuple_matcher!(A0 M0 0, A1 M1 1, A2 M2 2, A3 M3 3);
+tuple_matcher!(A0 M0 0, A1 M1 1, A2 M2 2, A3 M3 3, A4 M4 4);
+tuple_matcher!(A0 M0 0, A1 M1 1, A2 M2 2, A3 M3 3, A4 M4 4, A5 M5 5);
@@ -0,0 +1,111 @@
+/// Controls how a `Mock` stores the arguments of each call.
+///
+/// The default policy, `CloneArgs`, stores a full clone of every call's
+/// arguments. Other policies trade detail for cost: `ProjectArgs` keeps only
+/// a key derived from them and `DiscardArgs` keeps nothing beyond the number
+/// of calls.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{Mock, ProjectArgs};
+///
+/// #[derive(Clone)]
+/// struct Request {
+///     id: u64,
+///     body: Vec<u8>,
+/// }
+///
+/// let mock = Mock::<Request, (), _>::with_arg_record((), ProjectArgs(|req: &Request| req.id));
+///
+/// mock.call(Request { id: 1, body: vec![0; 1024] });
+///
+/// # #[cfg(not(feature = "disabled"))]
+/// assert!(mock.called_with(1u64));
+/// # #[cfg(not(feature = "disabled"))]
+/// assert_eq!(mock.calls(), vec![1]);
+/// ```
+pub trait ArgRecord<C> {
+    /// The stored representation of a single call's arguments.
+    type Record: Clone;
+
+    /// Converts the arguments of a call into the form stored by the `Mock`.
+    fn record(&self, args: &C) -> Self::Record;
+
+    /// Returns false if the `Mock` should keep no per-call history at all,
+    /// only counting calls. Defaults to true.
+    fn keeps_history(&self) -> bool {
+        true
+    }
+}
+
+/// Stores a clone of the arguments of every call.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CloneArgs;
+
+impl<C: Clone> ArgRecord<C> for CloneArgs {
+    type Record = C;
+
+    fn record(&self, args: &C) -> C {
+        args.clone()
+    }
+}
+
+/// Stores only a key projected from the arguments of every call.
+///
+/// Since the `Mock` passes the arguments on to its behavior, recording them in
+/// full always takes a copy. A cheap key, such as an id, avoids copying large
+/// arguments.
+pub struct ProjectArgs<C, K>(pub fn(&C) -> K);
+
+impl<C, K> ArgRecord<C> for ProjectArgs<C, K>
+where
+    K: Clone,
+{
+    type Record = K;
+
+    fn record(&self, args: &C) -> K {
+        (self.0)(args)
+    }
+}
+
+impl<C, K> Clone for ProjectArgs<C, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C, K> Copy for ProjectArgs<C, K> {}
+
+/// Stores nothing about each call; only the number of calls is tracked.
+///
+/// The call history stays empty, so methods that inspect it, such as
+/// `Mock::calls` or `Mock::calls_with_timestamps`, find no calls. Use
+/// `Mock::num_calls` instead.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{DiscardArgs, Mock};
+///
+/// let mock = Mock::<String, (), _>::with_arg_record((), DiscardArgs);
+///
+/// mock.call("a large payload".to_string());
+///
+/// # #[cfg(not(feature = "disabled"))]
+/// assert_eq!(mock.num_calls(), 1);
+/// # #[cfg(not(feature = "disabled"))]
+/// assert!(mock.calls().is_empty());
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiscardArgs;
+
+impl<C> ArgRecord<C> for DiscardArgs {
+    type Record = ();
+
+    fn record(&self, _: &C) {}
+
+    fn keeps_history(&self) -> bool {
+        false
+    }
+}
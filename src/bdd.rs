@@ -0,0 +1,218 @@
+//! Given/when/then style wrappers around `Mock`.
+//!
+//! These are purely sugar over the existing `Mock` methods, for tests written
+//! in a behavior-driven style.
+//!
+//! # Examples
+//!
+//! ```
+//! use pseudo::Mock;
+//! use pseudo::bdd::given;
+//! # #[cfg(not(feature = "disabled"))]
+//! use pseudo::bdd::then;
+//!
+//! let lookup = Mock::<&str, Option<u64>>::default();
+//!
+//! // Given
+//! given(&lookup).returns(Some(42));
+//!
+//! // When
+//! let result = lookup.call("answer");
+//!
+//! // Then
+//! assert_eq!(result, Some(42));
+//! # #[cfg(not(feature = "disabled"))]
+//! then(&lookup).was_called_once().was_called_with("answer");
+//! ```
+
+#[cfg(not(feature = "disabled"))]
+use std::fmt::Debug;
+
+use arg_record::ArgRecord;
+use mock::Mock;
+
+/// Start configuring the behavior of `mock`.
+pub fn given<'a, C, R, A>(mock: &'a Mock<C, R, A>) -> Given<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    Given { mock }
+}
+
+/// Start asserting on the interactions recorded by `mock`.
+#[cfg(not(feature = "disabled"))]
+pub fn then<'a, C, R, A>(mock: &'a Mock<C, R, A>) -> Then<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    Then { mock }
+}
+
+/// Configures the behavior of a `Mock`. Created by `given`.
+pub struct Given<'a, C, R, A>
+where
+    C: 'a,
+    R: Clone + 'a,
+    A: ArgRecord<C> + 'a,
+{
+    mock: &'a Mock<C, R, A>,
+}
+
+impl<'a, C, R, A> Given<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    /// Equivalent to `Mock::return_value`.
+    pub fn returns<T: Into<R>>(self, return_value: T) -> Self {
+        self.mock.return_value(return_value);
+        self
+    }
+
+    /// Equivalent to `Mock::use_fn`.
+    pub fn returns_with(self, mock_fn: fn(C) -> R) -> Self {
+        self.mock.use_fn(mock_fn);
+        self
+    }
+
+    /// Equivalent to `Mock::use_closure`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    /// use pseudo::bdd::given;
+    ///
+    /// let mock = Mock::<i64, i64>::default();
+    /// given(&mock).returns_with_closure(Box::new(|x| x * 2));
+    ///
+    /// assert_eq!(mock.call(21), 42);
+    /// ```
+    pub fn returns_with_closure(self, mock_fn: Box<dyn Fn(C) -> R + Send + Sync>) -> Self {
+        self.mock.use_closure(mock_fn);
+        self
+    }
+}
+
+/// Asserts on the interactions recorded by a `Mock`. Created by `then`.
+///
+/// Each assertion panics with a descriptive message if it does not hold.
+#[cfg(not(feature = "disabled"))]
+pub struct Then<'a, C, R, A>
+where
+    C: 'a,
+    R: Clone + 'a,
+    A: ArgRecord<C> + 'a,
+{
+    mock: &'a Mock<C, R, A>,
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<'a, C, R, A> Then<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    /// Asserts that the `Mock` has been called.
+    pub fn was_called(self) -> Self {
+        assert!(self.mock.called(), "expected mock to have been called");
+        self
+    }
+
+    /// Asserts that the `Mock` has not been called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    /// use pseudo::bdd::then;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    ///
+    /// then(&mock).was_not_called();
+    /// ```
+    pub fn was_not_called(self) -> Self {
+        assert!(
+            !self.mock.called(),
+            "expected mock not to have been called, but it was called {} time(s)",
+            self.mock.num_calls()
+        );
+        self
+    }
+
+    /// Asserts that the `Mock` has been called exactly once.
+    pub fn was_called_once(self) -> Self {
+        self.was_called_times(1)
+    }
+
+    /// Asserts that the `Mock` has been called exactly `times` times.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    /// use pseudo::bdd::then;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    /// mock.call(1);
+    ///
+    /// then(&mock).was_called_times(2);
+    /// ```
+    pub fn was_called_times(self, times: usize) -> Self {
+        let num_calls = self.mock.num_calls();
+        assert!(
+            num_calls == times,
+            "expected mock to have been called {} time(s), but it was called {} time(s)",
+            times,
+            num_calls
+        );
+        self
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<'a, C, R, A> Then<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: PartialEq + Debug,
+{
+    /// Asserts that the `Mock` has been called with the specified argument.
+    pub fn was_called_with<T: Into<A::Record>>(self, args: T) -> Self {
+        let args = args.into();
+        assert!(
+            self.mock.called_with(args.clone()),
+            "expected mock to have been called with {}, but its calls were {}",
+            self.mock.debug_args(&args),
+            self.mock.debug_calls()
+        );
+        self
+    }
+
+    /// Asserts that the `Mock` has not been called with the specified
+    /// argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    /// use pseudo::bdd::then;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("foo");
+    ///
+    /// then(&mock).was_called_with("foo").was_not_called_with("bar");
+    /// ```
+    pub fn was_not_called_with<T: Into<A::Record>>(self, args: T) -> Self {
+        let args = args.into();
+        assert!(
+            !self.mock.called_with(args.clone()),
+            "expected mock not to have been called with {}, but its calls were {}",
+            self.mock.debug_args(&args),
+            self.mock.debug_calls()
+        );
+        self
+    }
+}
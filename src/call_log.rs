@@ -0,0 +1,101 @@
+use std::sync::{Arc, RwLock};
+
+/// A single, ordered log of calls made to several `Mock`s.
+///
+/// Each `Mock` only tracks its own calls, which loses the relative order of
+/// calls to different methods of the same dependency. Registering the mocks
+/// with a shared `CallLog` via `Mock::log_calls_to` records every call as one
+/// value of a user-defined type, typically an enum with a variant per method.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{CallLog, Mock};
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Call {
+///     Connect(String),
+///     Send(Vec<u8>),
+///     Close,
+/// }
+///
+/// let log = CallLog::new();
+///
+/// let connect = Mock::<String, ()>::default();
+/// let send = Mock::<Vec<u8>, ()>::default();
+/// let close = Mock::<(), ()>::default();
+///
+/// connect.log_calls_to(&log, Call::Connect);
+/// send.log_calls_to(&log, Call::Send);
+/// close.log_calls_to(&log, |_| Call::Close);
+///
+/// connect.call("localhost".to_string());
+/// send.call(vec![1, 2, 3]);
+/// close.call(());
+///
+/// assert_eq!(
+///     log.calls(),
+///     vec![
+///         Call::Connect("localhost".to_string()),
+///         Call::Send(vec![1, 2, 3]),
+///         Call::Close,
+///     ]
+/// );
+/// ```
+#[derive(Debug)]
+pub struct CallLog<E> {
+    calls: Arc<RwLock<Vec<E>>>,
+}
+
+impl<E> CallLog<E> {
+    /// Creates a new, empty `CallLog`.
+    pub fn new() -> Self {
+        CallLog {
+            calls: Arc::new(RwLock::new(vec![])),
+        }
+    }
+
+    /// Returns true if any registered `Mock` has been called.
+    pub fn called(&self) -> bool {
+        !self.calls.read().unwrap().is_empty()
+    }
+
+    /// Returns the number of calls made to all registered `Mock`s.
+    pub fn num_calls(&self) -> usize {
+        self.calls.read().unwrap().len()
+    }
+
+    /// Reset the log.
+    pub fn reset_calls(&self) {
+        self.calls.write().unwrap().clear()
+    }
+
+    pub(crate) fn push(&self, call: E) {
+        self.calls.write().unwrap().push(call)
+    }
+}
+
+impl<E> Clone for CallLog<E> {
+    fn clone(&self) -> Self {
+        CallLog {
+            calls: self.calls.clone(),
+        }
+    }
+}
+
+impl<E> Default for CallLog<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> CallLog<E>
+where
+    E: Clone,
+{
+    /// Returns the calls made to all registered `Mock`s in order from first
+    /// to last.
+    pub fn calls(&self) -> Vec<E> {
+        self.calls.read().unwrap().clone()
+    }
+}
@@ -0,0 +1,62 @@
+/// The behavior of a `Mock` that determined the return value of a call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReturnSource {
+    /// The return value given at construction time or set via
+    /// `Mock::return_value` or one of its derivatives.
+    Value,
+    /// The function set via `Mock::use_fn`.
+    Fn,
+    /// The closure set via `Mock::use_closure` or one of its derivatives,
+    /// such as `Mock::panic_with`.
+    Closure,
+    /// The closure set via `Mock::use_indexed_closure`.
+    IndexedClosure,
+    /// The next entry of the sequence set via `Mock::return_values` or one
+    /// of its derivatives.
+    Sequence,
+    /// The return value set for the call's arguments via
+    /// `Mock::return_value_for` or `Mock::when`.
+    Stub,
+    /// The return value set via `Mock::on_nth_call`.
+    NthCall,
+    /// The value or function set via `Mock::return_value_once` or one of its
+    /// derivatives.
+    Once,
+    /// The function set via `Mock::delegate_to`.
+    Delegate,
+}
+
+/// The arguments of a recorded call to a `Mock`, along with the behavior
+/// that determined its return value.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{Mock, ReturnSource};
+///
+/// let mock = Mock::<i64, i64>::new(0);
+/// mock.call(1);
+/// mock.use_fn(|x| x * 2);
+/// mock.call(2);
+///
+/// let sources: Vec<_> = mock
+///     .call_records()
+///     .into_iter()
+///     .map(|record| record.source)
+///     .collect();
+///
+/// assert_eq!(
+///     sources,
+///     vec![Some(ReturnSource::Value), Some(ReturnSource::Fn)]
+/// );
+/// ```
+#[cfg(not(feature = "disabled"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallRecord<T> {
+    /// The arguments of the call, as stored by the `Mock`'s `ArgRecord`
+    /// policy.
+    pub args: T,
+    /// The behavior that determined the return value, or `None` if the call
+    /// panicked before one was chosen or is still in progress.
+    pub source: Option<ReturnSource>,
+}
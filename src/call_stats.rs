@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Instant;
+
+/// Summary statistics of a `Mock`'s call history, returned by `Mock::stats`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallStats<T>
+where
+    T: Hash + Eq,
+{
+    /// The total number of calls.
+    pub num_calls: usize,
+    /// The number of distinct arguments recorded.
+    pub num_distinct: usize,
+    /// The number of calls made with each distinct argument.
+    pub frequencies: HashMap<T, usize>,
+    /// The time of the earliest recorded call, if timestamps are being
+    /// recorded via `Mock::record_timestamps`.
+    pub first_call: Option<Instant>,
+    /// The time of the latest recorded call, if timestamps are being
+    /// recorded via `Mock::record_timestamps`.
+    pub last_call: Option<Instant>,
+}
+
+impl<T> CallStats<T>
+where
+    T: Clone + Hash + Eq,
+{
+    pub(crate) fn new(num_calls: usize, calls: &[T], timestamps: &[Instant]) -> Self {
+        let mut frequencies = HashMap::new();
+
+        for args in calls {
+            *frequencies.entry(args.clone()).or_insert(0) += 1;
+        }
+
+        CallStats {
+            num_calls,
+            num_distinct: frequencies.len(),
+            frequencies,
+            first_call: timestamps.first().cloned(),
+            last_call: timestamps.last().cloned(),
+        }
+    }
+
+    /// Returns the number of calls made with `args`.
+    pub fn frequency(&self, args: &T) -> usize {
+        self.frequencies.get(args).cloned().unwrap_or(0)
+    }
+}
@@ -0,0 +1,72 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+pub(crate) type Context = Arc<dyn Any + Send + Sync>;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+/// Returns a clone of the context of the `Mock` whose function is currently
+/// running on this thread, if it has a context of type `T`.
+///
+/// This lets functions set via `Mock::use_fn`, which cannot capture any
+/// state, behave according to test state set with `Mock::set_context`.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::Mock;
+///
+/// #[derive(Clone)]
+/// struct Quota(u64);
+///
+/// fn within_quota(bytes: u64) -> bool {
+///     match pseudo::context::<Quota>() {
+///         Some(Quota(limit)) => bytes <= limit,
+///         None => true,
+///     }
+/// }
+///
+/// let mock = Mock::<u64, bool>::default();
+/// mock.use_fn(within_quota);
+///
+/// assert!(mock.call(1_000));
+///
+/// mock.set_context(Quota(100));
+///
+/// assert!(mock.call(10));
+/// assert!(!mock.call(1_000));
+/// ```
+pub fn context<T>() -> Option<T>
+where
+    T: Any + Clone,
+{
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .and_then(|context| context.downcast_ref::<T>().cloned())
+    })
+}
+
+/// Makes `context` available to `pseudo::context` while `f` runs.
+pub(crate) fn with_context<F, R>(context: Option<Context>, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    struct Restore(Option<Context>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            CURRENT.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+
+    let previous = CURRENT.with(|current| current.replace(context));
+    let _restore = Restore(previous);
+
+    f()
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+
+/// Limits on how much of a call history is included in `Debug` output and
+/// failure messages.
+#[cfg(not(feature = "disabled"))]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct DebugLimits {
+    pub(crate) max_arg_len: Option<usize>,
+    pub(crate) max_calls: Option<usize>,
+}
+
+#[cfg(not(feature = "disabled"))]
+impl DebugLimits {
+    /// Formats a single call's arguments, truncated to `max_arg_len` bytes.
+    pub(crate) fn format_args<T: Debug>(&self, args: &T) -> String {
+        let formatted = format!("{:?}", args);
+
+        match self.max_arg_len {
+            Some(max_len) if formatted.len() > max_len => {
+                let mut end = max_len;
+
+                while !formatted.is_char_boundary(end) {
+                    end -= 1;
+                }
+
+                format!(
+                    "{}... ({} more bytes)",
+                    &formatted[..end],
+                    formatted.len() - end
+                )
+            }
+            _ => formatted,
+        }
+    }
+
+    /// Formats a call history, showing at most `max_calls` calls.
+    pub(crate) fn format_calls<T: Debug>(&self, calls: &[T]) -> String {
+        let shown = self.max_calls.unwrap_or(calls.len()).min(calls.len());
+        let mut formatted = calls[..shown]
+            .iter()
+            .map(|args| self.format_args(args))
+            .collect::<Vec<_>>();
+
+        if shown < calls.len() {
+            formatted.push(format!("... ({} more calls)", calls.len() - shown));
+        }
+
+        format!("[{}]", formatted.join(", "))
+    }
+}
+
+/// Writes a preformatted string as-is from a `Debug` implementation.
+pub(crate) struct Formatted(pub(crate) String);
+
+impl Debug for Formatted {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt::Debug;
+
+use debug_limits::DebugLimits;
+
+/// Renders a positional diff of two call histories for failure messages.
+///
+/// Calls that match are prefixed with two spaces, expected calls that are
+/// missing or different are prefixed with `-` and actual calls that are
+/// unexpected or different are prefixed with `+`.
+pub(crate) fn diff_calls<T>(expected: &[T], actual: &[T], limits: &DebugLimits) -> String
+where
+    T: PartialEq + Debug,
+{
+    let mut lines = vec![];
+
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                lines.push(format!("  {}: {}", i, limits.format_args(actual)));
+            }
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    lines.push(format!("- {}: {}", i, limits.format_args(expected)));
+                }
+
+                if let Some(actual) = actual {
+                    lines.push(format!("+ {}: {}", i, limits.format_args(actual)));
+                }
+            }
+        }
+    }
+
+    lines.join("\n")
+}
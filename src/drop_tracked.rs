@@ -0,0 +1,119 @@
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A return value that keeps track of when the values returned by a `Mock`
+/// are dropped.
+///
+/// Each clone of a `DropTracked` counts as a returned value, and dropping a
+/// returned value is recorded. The original value stored by the `Mock` is not
+/// counted. This allows tests to verify RAII contracts, such as a guard being
+/// released by the code under test.
+///
+/// Only values cloned from the `Mock`'s return value are tracked; values
+/// produced by `Mock::use_fn` or `Mock::use_closure` are not.
+///
+/// Clones made by the `Mock` itself are counted too, since they cannot be
+/// told apart from values returned by `Mock::call`. A value obtained via
+/// `Mock::returns` or `Mock::calls_and_returns` counts as alive until it is
+/// dropped, and with `Mock::record_returns` each returned value is kept alive
+/// by the `Mock` until its call history is reset.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{DropTracked, Mock};
+///
+/// let lock = Mock::<(), DropTracked<()>>::new(DropTracked::new(()));
+///
+/// let guard = lock.call(());
+///
+/// assert_eq!(lock.returned_values_alive(), 1);
+/// assert_eq!(lock.returned_values_dropped(), 0);
+///
+/// drop(guard);
+///
+/// assert_eq!(lock.returned_values_alive(), 0);
+/// assert_eq!(lock.returned_values_dropped(), 1);
+/// ```
+pub struct DropTracked<T> {
+    value: T,
+    returned: bool,
+    num_returned: Arc<AtomicUsize>,
+    num_dropped: Arc<AtomicUsize>,
+}
+
+impl<T> DropTracked<T> {
+    /// Wraps `value` so that drops of its clones are tracked.
+    pub fn new(value: T) -> Self {
+        DropTracked {
+            value,
+            returned: false,
+            num_returned: Arc::new(AtomicUsize::new(0)),
+            num_dropped: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns the number of clones that have been made.
+    pub fn num_returned(&self) -> usize {
+        self.num_returned.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of clones that have been dropped.
+    pub fn num_dropped(&self) -> usize {
+        self.num_dropped.load(Ordering::SeqCst)
+    }
+}
+
+impl<T> Clone for DropTracked<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.num_returned.fetch_add(1, Ordering::SeqCst);
+
+        DropTracked {
+            value: self.value.clone(),
+            returned: true,
+            num_returned: self.num_returned.clone(),
+            num_dropped: self.num_dropped.clone(),
+        }
+    }
+}
+
+impl<T> Drop for DropTracked<T> {
+    fn drop(&mut self) {
+        if self.returned {
+            self.num_dropped.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl<T> Deref for DropTracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for DropTracked<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> Debug for DropTracked<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("DropTracked")
+            .field("value", &self.value)
+            .field("num_returned", &self.num_returned())
+            .field("num_dropped", &self.num_dropped())
+            .finish()
+    }
+}
@@ -0,0 +1,294 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+
+use any_mock::AnyMock;
+#[cfg(not(feature = "disabled"))]
+use verification_error::VerificationError;
+
+/// A mock for a whole trait, with behaviors looked up by method name at
+/// runtime.
+///
+/// `DynMock` trades compile-time type checking for brevity: instead of one
+/// `Mock` field per method, a single `DynMock` backs an adapter struct and
+/// each method forwards to `DynMock::call` with its name. Calling a method
+/// with no configured behavior, or with types that don't match the
+/// configured behavior, panics.
+///
+/// Each method is backed by an `AnyMock` named after it, so verification
+/// errors identify the method.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::DynMock;
+///
+/// trait Repository {
+///     fn get(&self, id: u64) -> Option<String>;
+///     fn count(&self) -> usize;
+/// }
+///
+/// struct MockRepository(DynMock);
+///
+/// impl Repository for MockRepository {
+///     fn get(&self, id: u64) -> Option<String> {
+///         self.0.call("get", id)
+///     }
+///
+///     fn count(&self) -> usize {
+///         self.0.call("count", ())
+///     }
+/// }
+///
+/// let mock = DynMock::new();
+/// mock.use_closure("get", Box::new(|id: u64| Some(format!("item {}", id))));
+/// mock.return_value("count", 1usize);
+///
+/// let repository = MockRepository(mock.clone());
+///
+/// assert_eq!(repository.get(7), Some("item 7".to_string()));
+/// assert_eq!(repository.count(), 1);
+/// # #[cfg(not(feature = "disabled"))]
+/// assert!(mock.called_with("get", 7u64));
+/// # #[cfg(not(feature = "disabled"))]
+/// assert_eq!(mock.num_calls("count"), 1);
+/// ```
+#[derive(Clone, Default)]
+pub struct DynMock {
+    methods: Arc<RwLock<HashMap<String, AnyMock>>>,
+}
+
+impl DynMock {
+    /// Creates a new `DynMock` with no configured behaviors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the `DynMock` to return a value for `method`, keeping track of the
+    /// arguments used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no behavior has been configured for `method`, or if `C` or
+    /// `R` don't match the types of the configured behavior.
+    pub fn call<C, R>(&self, method: &str, args: C) -> R
+    where
+        C: Any + Send + Sync,
+        R: Any,
+    {
+        match self.method(method).call(Box::new(args)).downcast() {
+            Ok(return_value) => *return_value,
+            Err(_) => panic!("method `{}` was called with the wrong return type", method),
+        }
+    }
+
+    /// Return `return_value` from calls to `method`, regardless of the
+    /// arguments.
+    pub fn return_value<R>(&self, method: &str, return_value: R)
+    where
+        R: Any + Clone + Send + Sync,
+    {
+        self.method(method).return_value(return_value)
+    }
+
+    /// Specify a function to determine the return value of `method` based on
+    /// its arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::DynMock;
+    ///
+    /// fn add((x, y): (i64, i64)) -> i64 {
+    ///     x + y
+    /// }
+    ///
+    /// let mock = DynMock::new();
+    /// mock.use_fn("add", add);
+    ///
+    /// assert_eq!(mock.call::<_, i64>("add", (1i64, 2i64)), 3);
+    /// ```
+    pub fn use_fn<C, R>(&self, method: &str, mock_fn: fn(C) -> R)
+    where
+        C: Any + Clone,
+        R: Any + Send,
+    {
+        self.use_closure(method, Box::new(mock_fn))
+    }
+
+    /// Specify a closure to determine the return value of `method` based on
+    /// its arguments.
+    pub fn use_closure<C, R>(&self, method: &str, mock_fn: Box<dyn Fn(C) -> R + Send + Sync>)
+    where
+        C: Any + Clone,
+        R: Any + Send,
+    {
+        let name = method.to_string();
+        self.method(method)
+            .use_closure(Box::new(move |args| match args.downcast_ref::<C>() {
+                Some(args) => Box::new(mock_fn(args.clone())),
+                None => panic!("method `{}` was called with the wrong argument type", name),
+            }))
+    }
+
+    /// Expect `method` to be called exactly `n` times, as checked by
+    /// `DynMock::verify`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::DynMock;
+    ///
+    /// let mock = DynMock::new();
+    /// mock.return_value("flush", ());
+    /// mock.expect_calls("flush", 1);
+    ///
+    /// assert_eq!(mock.verify().unwrap_err().mock_name, Some("flush".to_string()));
+    ///
+    /// mock.call::<_, ()>("flush", ());
+    ///
+    /// assert!(mock.verify().is_ok());
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn expect_calls(&self, method: &str, n: usize) {
+        self.method(method).expect_calls(n)
+    }
+
+    /// Returns an error for the first method, by name, whose expectations
+    /// have not been met.
+    #[cfg(not(feature = "disabled"))]
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        let methods = self.methods.read().unwrap();
+        let mut names: Vec<&String> = methods.keys().collect();
+        names.sort();
+
+        names
+            .into_iter()
+            .try_for_each(|name| methods[name].verify())
+    }
+
+    /// Returns true if `method` has been called.
+    #[cfg(not(feature = "disabled"))]
+    pub fn called(&self, method: &str) -> bool {
+        self.num_calls(method) > 0
+    }
+
+    /// Returns the number of times `method` has been called.
+    #[cfg(not(feature = "disabled"))]
+    pub fn num_calls(&self, method: &str) -> usize {
+        self.methods
+            .read()
+            .unwrap()
+            .get(method)
+            .map_or(0, AnyMock::num_calls)
+    }
+
+    /// Returns the arguments `method` has been called with, in order from
+    /// first to last.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `C` doesn't match the type `method` was called with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::DynMock;
+    ///
+    /// let mock = DynMock::new();
+    /// mock.return_value("greet", ());
+    ///
+    /// mock.call::<_, ()>("greet", "Fido");
+    /// mock.call::<_, ()>("greet", "Spot");
+    ///
+    /// assert_eq!(mock.calls::<&str>("greet"), vec!["Fido", "Spot"]);
+    /// assert!(mock.calls::<&str>("other").is_empty());
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls<C>(&self, method: &str) -> Vec<C>
+    where
+        C: Any + Clone,
+    {
+        let methods = self.methods.read().unwrap();
+        let mock = match methods.get(method) {
+            Some(mock) => mock,
+            None => return vec![],
+        };
+        let calls = mock.calls_of::<C>();
+
+        if calls.len() != mock.num_calls() {
+            panic!(
+                "method `{}` was called with a different argument type",
+                method
+            );
+        }
+
+        calls
+    }
+
+    /// Returns true if `method` has been called with the specified argument.
+    #[cfg(not(feature = "disabled"))]
+    pub fn called_with<C>(&self, method: &str, args: C) -> bool
+    where
+        C: Any + PartialEq,
+    {
+        self.methods
+            .read()
+            .unwrap()
+            .get(method)
+            .is_some_and(|mock| mock.called_with(args))
+    }
+
+    /// Reset the call history of every method.
+    #[cfg(not(feature = "disabled"))]
+    pub fn reset_calls(&self) {
+        for mock in self.methods.read().unwrap().values() {
+            mock.reset_calls();
+        }
+    }
+
+    /// Returns the `AnyMock` backing `method`, creating one that panics when
+    /// called if there is none yet.
+    fn method(&self, method: &str) -> AnyMock {
+        if let Some(mock) = self.methods.read().unwrap().get(method) {
+            return mock.clone();
+        }
+
+        self.methods
+            .write()
+            .unwrap()
+            .entry(method.to_string())
+            .or_insert_with(|| {
+                let name = method.to_string();
+                let mock = AnyMock::with_closure(Box::new(move |_| {
+                    panic!("no behavior configured for method `{}`", name)
+                }));
+                #[cfg(not(feature = "disabled"))]
+                mock.set_name(method);
+                mock
+            })
+            .clone()
+    }
+}
+
+impl Debug for DynMock {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("DynMock");
+
+        #[cfg(not(feature = "disabled"))]
+        debug.field(
+            "num_calls",
+            &self
+                .methods
+                .read()
+                .unwrap()
+                .iter()
+                .map(|(method, mock)| (method.clone(), mock.num_calls()))
+                .collect::<HashMap<_, _>>(),
+        );
+
+        debug.finish()
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt::Debug;
+
+use debug_limits::DebugLimits;
+
+/// An expectation about a `Mock`'s calls, registered via a method such as
+/// `Mock::expect_calls` and checked by `Mock::verify`.
+#[derive(Clone, Debug)]
+pub(crate) enum Expectation<T> {
+    Calls(usize),
+    AtLeast(usize),
+    AtMost(usize),
+    CalledWith(T),
+}
+
+impl<T> Expectation<T>
+where
+    T: PartialEq + Debug,
+{
+    /// Describes the expected interaction.
+    pub(crate) fn describe(&self, limits: &DebugLimits) -> String {
+        match *self {
+            Expectation::Calls(n) => format!("exactly {}", plural(n, "call")),
+            Expectation::AtLeast(n) => format!("at least {}", plural(n, "call")),
+            Expectation::AtMost(n) => format!("at most {}", plural(n, "call")),
+            Expectation::CalledWith(ref args) => {
+                format!("a call with {}", limits.format_args(args))
+            }
+        }
+    }
+
+    /// Returns an error describing how the call history fails to meet the
+    /// expectation, if it does.
+    pub(crate) fn check(
+        &self,
+        num_calls: usize,
+        calls: &[T],
+        limits: &DebugLimits,
+    ) -> Result<(), String> {
+        let met = match *self {
+            Expectation::Calls(n) => num_calls == n,
+            Expectation::AtLeast(n) => num_calls >= n,
+            Expectation::AtMost(n) => num_calls <= n,
+            Expectation::CalledWith(ref args) => calls.contains(args),
+        };
+
+        if met {
+            return Ok(());
+        }
+
+        let actual = match *self {
+            Expectation::Calls(_) | Expectation::AtLeast(_) | Expectation::AtMost(_) => {
+                format!("it was called {}", plural(num_calls, "time"))
+            }
+            Expectation::CalledWith(_) => format!("its calls were {}", limits.format_calls(calls)),
+        };
+
+        Err(format!(
+            "expected {}, but {}",
+            self.describe(limits),
+            actual
+        ))
+    }
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("{} {}", n, noun)
+    } else {
+        format!("{} {}s", n, noun)
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+use std::sync::{Arc, RwLock};
+
+/// An in-memory key-value store for backing repository traits, with call
+/// tracking and per-key error injection.
+///
+/// Unlike a `Mock`, a `FakeStore` is stateful: values written with
+/// `FakeStore::put` are returned by later calls to `FakeStore::get`. Every
+/// operation is still recorded, whether or not it succeeded, so tests can
+/// verify how the store was used.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::FakeStore;
+///
+/// trait UserRepo {
+///     fn find(&self, id: &u64) -> Result<Option<String>, ()>;
+///     fn save(&self, id: u64, name: String) -> Result<(), ()>;
+/// }
+///
+/// impl UserRepo for FakeStore<u64, String> {
+///     fn find(&self, id: &u64) -> Result<Option<String>, ()> {
+///         self.get(id)
+///     }
+///
+///     fn save(&self, id: u64, name: String) -> Result<(), ()> {
+///         self.put(id, name).map(|_| ())
+///     }
+/// }
+///
+/// fn rename<T: UserRepo>(repo: &T, id: u64, name: &str) -> Result<bool, ()> {
+///     match repo.find(&id)? {
+///         Some(_) => repo.save(id, name.to_string()).map(|_| true),
+///         None => Ok(false),
+///     }
+/// }
+///
+/// let store = FakeStore::new();
+/// store.put(1, "alice".to_string()).unwrap();
+/// store.reset_calls();
+///
+/// assert_eq!(rename(&store, 1, "alicia"), Ok(true));
+/// assert_eq!(rename(&store, 2, "bob"), Ok(false));
+///
+/// assert_eq!(store.get(&1), Ok(Some("alicia".to_string())));
+/// assert_eq!(store.gets(), vec![1, 2, 1]);
+/// assert_eq!(store.puts(), vec![(1, "alicia".to_string())]);
+/// ```
+pub struct FakeStore<K, V, E = ()> {
+    entries: Arc<RwLock<HashMap<K, V>>>,
+    failures: Arc<RwLock<HashMap<K, E>>>,
+    gets: Arc<RwLock<Vec<K>>>,
+    puts: Arc<RwLock<Vec<(K, V)>>>,
+    deletes: Arc<RwLock<Vec<K>>>,
+}
+
+impl<K, V, E> FakeStore<K, V, E>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    E: Clone,
+{
+    /// Creates a new, empty `FakeStore`.
+    pub fn new() -> Self {
+        FakeStore {
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            failures: Arc::new(RwLock::new(HashMap::new())),
+            gets: Arc::new(RwLock::new(vec![])),
+            puts: Arc::new(RwLock::new(vec![])),
+            deletes: Arc::new(RwLock::new(vec![])),
+        }
+    }
+
+    /// Returns the value stored for `key`, or the error injected for `key`
+    /// with `FakeStore::fail_key`.
+    pub fn get(&self, key: &K) -> Result<Option<V>, E> {
+        self.gets.write().unwrap().push(key.clone());
+        self.check(key)?;
+
+        Ok(self.entries.read().unwrap().get(key).cloned())
+    }
+
+    /// Stores `value` for `key`, returning the previous value if there was
+    /// one, or the error injected for `key` with `FakeStore::fail_key`.
+    ///
+    /// The store is left unchanged when an error is returned.
+    pub fn put(&self, key: K, value: V) -> Result<Option<V>, E> {
+        self.puts
+            .write()
+            .unwrap()
+            .push((key.clone(), value.clone()));
+        self.check(&key)?;
+
+        Ok(self.entries.write().unwrap().insert(key, value))
+    }
+
+    /// Removes the value stored for `key`, returning it if there was one, or
+    /// the error injected for `key` with `FakeStore::fail_key`.
+    ///
+    /// The store is left unchanged when an error is returned.
+    pub fn delete(&self, key: &K) -> Result<Option<V>, E> {
+        self.deletes.write().unwrap().push(key.clone());
+        self.check(key)?;
+
+        Ok(self.entries.write().unwrap().remove(key))
+    }
+
+    /// Make every operation on `key` fail with `err`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::FakeStore;
+    ///
+    /// let store = FakeStore::<&str, u64, &str>::new();
+    /// store.fail_key("locked", "permission denied");
+    ///
+    /// assert_eq!(store.put("locked", 1), Err("permission denied"));
+    /// assert_eq!(store.put("open", 2), Ok(None));
+    /// assert_eq!(store.get(&"locked"), Err("permission denied"));
+    /// assert_eq!(store.puts(), vec![("locked", 1), ("open", 2)]);
+    ///
+    /// store.clear_failures();
+    ///
+    /// assert_eq!(store.get(&"locked"), Ok(None));
+    /// ```
+    pub fn fail_key(&self, key: K, err: E) {
+        self.failures.write().unwrap().insert(key, err);
+    }
+
+    /// Remove all errors injected with `FakeStore::fail_key`.
+    pub fn clear_failures(&self) {
+        self.failures.write().unwrap().clear()
+    }
+
+    /// Returns a copy of the entries currently in the store, without
+    /// recording a call.
+    pub fn entries(&self) -> HashMap<K, V> {
+        self.entries.read().unwrap().clone()
+    }
+
+    /// Returns the keys used for `FakeStore::get`, in order from first to
+    /// last.
+    pub fn gets(&self) -> Vec<K> {
+        self.gets.read().unwrap().clone()
+    }
+
+    /// Returns the keys and values used for `FakeStore::put`, in order from
+    /// first to last.
+    pub fn puts(&self) -> Vec<(K, V)> {
+        self.puts.read().unwrap().clone()
+    }
+
+    /// Returns the keys used for `FakeStore::delete`, in order from first to
+    /// last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::FakeStore;
+    ///
+    /// let store = FakeStore::<u64, &str>::new();
+    /// store.put(1, "one").unwrap();
+    ///
+    /// assert_eq!(store.delete(&1), Ok(Some("one")));
+    /// assert_eq!(store.delete(&1), Ok(None));
+    /// assert_eq!(store.deletes(), vec![1, 1]);
+    /// assert!(store.entries().is_empty());
+    /// ```
+    pub fn deletes(&self) -> Vec<K> {
+        self.deletes.read().unwrap().clone()
+    }
+
+    /// Returns the total number of operations performed on the store.
+    pub fn num_calls(&self) -> usize {
+        self.gets.read().unwrap().len()
+            + self.puts.read().unwrap().len()
+            + self.deletes.read().unwrap().len()
+    }
+
+    /// Reset the call history for the `FakeStore`, keeping its entries.
+    pub fn reset_calls(&self) {
+        self.gets.write().unwrap().clear();
+        self.puts.write().unwrap().clear();
+        self.deletes.write().unwrap().clear()
+    }
+
+    fn check(&self, key: &K) -> Result<(), E> {
+        match self.failures.read().unwrap().get(key) {
+            Some(err) => Err(err.clone()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<K, V, E> Default for FakeStore<K, V, E>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    E: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, E> Clone for FakeStore<K, V, E> {
+    fn clone(&self) -> Self {
+        FakeStore {
+            entries: self.entries.clone(),
+            failures: self.failures.clone(),
+            gets: self.gets.clone(),
+            puts: self.puts.clone(),
+            deletes: self.deletes.clone(),
+        }
+    }
+}
+
+impl<K, V, E> Debug for FakeStore<K, V, E>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("FakeStore")
+            .field("entries", &*self.entries.read().unwrap())
+            .field("gets", &*self.gets.read().unwrap())
+            .field("puts", &*self.puts.read().unwrap())
+            .field("deletes", &*self.deletes.read().unwrap())
+            .finish()
+    }
+}
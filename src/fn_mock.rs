@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex, RwLock};
+
+type OptionalRef<T> = Arc<RwLock<Option<T>>>;
+
+/// A variant of `Mock` whose return values always come from a function or
+/// closure.
+///
+/// Since `FnMock` never stores a return value, the return type does not need
+/// to implement `Clone`. This makes it possible to mock methods that return
+/// types such as `std::fs::File` or `std::sync::mpsc::Receiver`.
+pub struct FnMock<C, R>
+where
+    C: Clone,
+{
+    mock_fn: OptionalRef<fn(C) -> R>,
+    mock_closure: OptionalRef<Box<dyn Fn(C) -> R + Send + Sync>>,
+    once: Arc<Mutex<VecDeque<R>>>,
+    calls: Arc<RwLock<Vec<C>>>,
+}
+
+impl<C, R> FnMock<C, R>
+where
+    C: Clone,
+{
+    /// Creates a new `FnMock` that will return the output of `mock_fn`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::mpsc::{channel, Receiver};
+    ///
+    /// use pseudo::FnMock;
+    ///
+    /// fn subscribe(_: &str) -> Receiver<i64> {
+    ///     let (tx, rx) = channel();
+    ///     tx.send(42).unwrap();
+    ///     rx
+    /// }
+    ///
+    /// let mock = FnMock::<&str, Receiver<i64>>::new(subscribe);
+    ///
+    /// assert_eq!(mock.call("topic").recv(), Ok(42));
+    /// ```
+    pub fn new(mock_fn: fn(C) -> R) -> Self {
+        FnMock {
+            mock_fn: Arc::new(RwLock::new(Some(mock_fn))),
+            mock_closure: Arc::new(RwLock::new(None)),
+            once: Arc::new(Mutex::new(VecDeque::new())),
+            calls: Arc::new(RwLock::new(vec![])),
+        }
+    }
+
+    /// Creates a new `FnMock` that will return the output of `mock_fn`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::FnMock;
+    ///
+    /// struct Connection(u16);
+    ///
+    /// let mock = FnMock::<u16, Connection>::from_closure(Box::new(Connection));
+    ///
+    /// assert_eq!(mock.call(8080).0, 8080);
+    /// ```
+    pub fn from_closure(mock_fn: Box<dyn Fn(C) -> R + Send + Sync>) -> Self {
+        FnMock {
+            mock_fn: Arc::new(RwLock::new(None)),
+            mock_closure: Arc::new(RwLock::new(Some(mock_fn))),
+            once: Arc::new(Mutex::new(VecDeque::new())),
+            calls: Arc::new(RwLock::new(vec![])),
+        }
+    }
+
+    /// Creates a new `FnMock` that will return `return_value` from the first
+    /// call and panic on every call after that, unless a function or
+    /// closure is set.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use std::fs::File;
+    ///
+    /// use pseudo::FnMock;
+    ///
+    /// let open = FnMock::<&str, File>::once(File::open("Cargo.toml").unwrap());
+    ///
+    /// assert!(open.call("Cargo.toml").metadata().is_ok());
+    ///
+    /// // Panics, since the file was already returned
+    /// open.call("Cargo.toml");
+    /// ```
+    pub fn once(return_value: R) -> Self {
+        let mock = Self::new(already_returned::<C, R>);
+        mock.return_once(return_value);
+        mock
+    }
+
+    /// Use the `FnMock` to return a value, keeping track of the arguments
+    /// used.
+    ///
+    /// The value is the next one set via `FnMock::return_once`, if any, and
+    /// otherwise the output of whichever of `FnMock::use_fn` or
+    /// `FnMock::use_closure` was most recently called.
+    pub fn call(&self, args: C) -> R {
+        self.calls.write().unwrap().push(args.clone());
+
+        if let Some(return_value) = self.once.lock().unwrap().pop_front() {
+            return return_value;
+        }
+
+        if let Some(ref mock_fn) = *self.mock_fn.read().unwrap() {
+            return mock_fn(args);
+        }
+
+        if let Some(ref mock_closure) = *self.mock_closure.read().unwrap() {
+            return mock_closure(args);
+        }
+
+        unreachable!("FnMock always has a function or closure")
+    }
+
+    /// Return `return_value` from the next call only, then fall back to the
+    /// function or closure.
+    ///
+    /// Since the value is moved out rather than cloned, it does not need to
+    /// implement `Clone`. Calling this several times queues up values for
+    /// successive calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::mpsc::{channel, Receiver};
+    ///
+    /// use pseudo::FnMock;
+    ///
+    /// let (tx, rx) = channel();
+    /// tx.send("first").unwrap();
+    ///
+    /// let subscribe = FnMock::<(), Receiver<&str>>::new(|_| channel().1);
+    /// subscribe.return_once(rx);
+    ///
+    /// assert_eq!(subscribe.call(()).recv(), Ok("first"));
+    /// assert!(subscribe.call(()).recv().is_err());
+    /// ```
+    pub fn return_once(&self, return_value: R) {
+        self.once.lock().unwrap().push_back(return_value)
+    }
+
+    /// Specify a function to determine the `FnMock`'s return value based on
+    /// the arguments provided to `FnMock::call`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::FnMock;
+    ///
+    /// let mock = FnMock::<i64, String>::new(|x| x.to_string());
+    /// mock.use_fn(|x| format!("#{}", x));
+    ///
+    /// assert_eq!(mock.call(1), "#1");
+    /// ```
+    pub fn use_fn(&self, mock_fn: fn(C) -> R) {
+        let mut closure_value = self.mock_closure.write().unwrap();
+        *closure_value = None;
+
+        let mut fn_value = self.mock_fn.write().unwrap();
+        *fn_value = Some(mock_fn)
+    }
+
+    /// Specify a closure to determine the `FnMock`'s return value based on
+    /// the arguments provided to `FnMock::call`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::FnMock;
+    ///
+    /// let mock = FnMock::<i64, String>::new(|x| x.to_string());
+    /// let prefix = "#";
+    /// mock.use_closure(Box::new(move |x| format!("{}{}", prefix, x)));
+    ///
+    /// assert_eq!(mock.call(1), "#1");
+    /// ```
+    pub fn use_closure(&self, mock_fn: Box<dyn Fn(C) -> R + Send + Sync>) {
+        let mut fn_value = self.mock_fn.write().unwrap();
+        *fn_value = None;
+
+        let mut closure_value = self.mock_closure.write().unwrap();
+        *closure_value = Some(mock_fn)
+    }
+
+    /// Returns true if `FnMock::call` has been called.
+    pub fn called(&self) -> bool {
+        !self.calls.read().unwrap().is_empty()
+    }
+
+    /// Returns the number of times `FnMock::call` has been called.
+    pub fn num_calls(&self) -> usize {
+        self.calls.read().unwrap().len()
+    }
+
+    /// Returns the arguments to `FnMock::call` in order from first to last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::FnMock;
+    ///
+    /// let mock = FnMock::<&str, Vec<u8>>::new(|x| x.as_bytes().to_vec());
+    ///
+    /// mock.call("first");
+    /// mock.call("second");
+    ///
+    /// assert!(mock.called());
+    /// assert_eq!(mock.num_calls(), 2);
+    /// assert_eq!(mock.calls().as_slice(), ["first", "second"]);
+    /// ```
+    pub fn calls(&self) -> Vec<C> {
+        self.calls.read().unwrap().clone()
+    }
+
+    /// Reset the call history for the `FnMock`.
+    pub fn reset_calls(&self) {
+        self.calls.write().unwrap().clear()
+    }
+}
+
+impl<C, R> FnMock<C, R>
+where
+    C: Clone + PartialEq,
+{
+    /// Returns true if the specified argument has been used for
+    /// `FnMock::call`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::FnMock;
+    ///
+    /// let mock = FnMock::<&str, Box<str>>::new(Box::from);
+    /// mock.call("foo");
+    ///
+    /// assert!(mock.called_with("foo"));
+    /// assert!(!mock.called_with("bar"));
+    /// ```
+    pub fn called_with<T: Into<C>>(&self, args: T) -> bool {
+        self.calls.read().unwrap().contains(&args.into())
+    }
+}
+
+impl<C, R> Clone for FnMock<C, R>
+where
+    C: Clone,
+{
+    fn clone(&self) -> Self {
+        FnMock {
+            mock_fn: self.mock_fn.clone(),
+            mock_closure: self.mock_closure.clone(),
+            once: self.once.clone(),
+            calls: self.calls.clone(),
+        }
+    }
+}
+
+impl<C, R> Debug for FnMock<C, R>
+where
+    C: Clone + Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("FnMock")
+            .field("calls", &self.calls)
+            .finish()
+    }
+}
+
+fn already_returned<C, R>(_: C) -> R {
+    panic!("FnMock's value was already returned by a previous call")
+}
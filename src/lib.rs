@@ -1,7 +1,8 @@
 //! Pseudo is a small library for mocking `Trait` implementations.
 //!
 //! The `Mock` struct tracks function call arguments and specifies return
-//! values or function overrides.
+//! values or function overrides. The `FnMock` struct does the same for return
+//! types that do not implement `Clone`, using only function overrides.
 //!
 //! # Examples
 //!
@@ -38,20 +39,111 @@
 //! fn test_uses_correct_args() {
 //!     let mock = MockFoo { expensive_fn: Mock::default() };
 //!
+//! #     #[cfg(not(feature = "disabled"))]
 //!     assert!(!mock.expensive_fn.called());
 //!
 //!     double_expensive_fn(&mock, 1, 2);
 //!
+//! #     #[cfg(not(feature = "disabled"))]
 //!     assert_eq!(mock.expensive_fn.num_calls(), 1);
+//! #     #[cfg(not(feature = "disabled"))]
 //!     assert!(mock.expensive_fn.called_with((11, 2)));
 //! }
 //!
 //! test_doubles_return_value();
 //! test_uses_correct_args();
 //! ```
+//!
+//! # Features
+//!
+//! * `disabled`: compiles out `Mock`'s call tracking, so that `Mock::call`
+//!   only counts the call and returns the configured value or the output of
+//!   the configured function. This is intended for builds that include
+//!   mock-bearing types but never inspect them, such as production builds of
+//!   crates that ship test utilities alongside real code. Methods that
+//!   inspect or verify calls, such as `Mock::called_with` and `Mock::verify`,
+//!   are removed along with the types and macros built on them, so any code
+//!   that relies on them fails to compile rather than passing vacuously.
+//!   Other mock types, such as `FnMock` and `Spy`, are unaffected.
+//! * `serde`: implements `Serialize` for `Mock` when its recorded arguments
+//!   implement `Serialize`, and adds `Mock::calls_json` for exporting call
+//!   histories, such as to debug failures that only occur in CI.
+
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 
+#[macro_use]
+mod macros;
+
+pub use any_mock::AnyMock;
+#[cfg(not(feature = "disabled"))]
+pub use arg_matcher::{any, eq, Anything, ArgMatcher, Equals};
+pub use arg_record::{ArgRecord, CloneArgs, DiscardArgs, ProjectArgs};
+#[cfg(not(feature = "disabled"))]
+pub use call_log::CallLog;
+#[cfg(not(feature = "disabled"))]
+pub use call_record::CallRecord;
+pub use call_record::ReturnSource;
+#[cfg(not(feature = "disabled"))]
+pub use call_stats::CallStats;
+pub use context::context;
+pub use drop_tracked::DropTracked;
+pub use dyn_mock::DynMock;
+pub use fake_store::FakeStore;
+pub use fn_mock::FnMock;
 pub use mock::Mock;
+pub use nth_call::NthCall;
+pub use resolution::Resolution;
+pub use return_sequence::{Exhaustion, Stages};
+#[cfg(not(feature = "disabled"))]
+pub use sequence::Sequence;
+pub use spy::Spy;
+pub use strict_mock::{StrictMock, Stubbed, Unstubbed};
+pub use test_context::TestContext;
+#[cfg(not(feature = "disabled"))]
+pub use verification_error::VerificationError;
+#[cfg(not(feature = "disabled"))]
+pub use weak_mock::WeakMock;
+pub use when::When;
 
 pub type Pseudo<C, R> = Mock<C, R>;
 
+pub mod bdd;
+#[cfg(not(feature = "disabled"))]
+pub mod table;
+
+mod any_mock;
+#[cfg(not(feature = "disabled"))]
+mod arg_matcher;
+mod arg_record;
+#[cfg(not(feature = "disabled"))]
+mod call_log;
+mod call_record;
+#[cfg(not(feature = "disabled"))]
+mod call_stats;
+mod context;
+mod debug_limits;
+#[cfg(not(feature = "disabled"))]
+mod diff;
+mod drop_tracked;
+mod dyn_mock;
+#[cfg(not(feature = "disabled"))]
+mod expectation;
+mod fake_store;
+mod fn_mock;
 mod mock;
+mod nth_call;
+mod resolution;
+mod return_sequence;
+#[cfg(not(feature = "disabled"))]
+mod sequence;
+mod spy;
+mod strict_mock;
+mod test_context;
+#[cfg(not(feature = "disabled"))]
+mod verification_error;
+#[cfg(not(feature = "disabled"))]
+mod weak_mock;
+mod when;
@@ -0,0 +1,209 @@
+/// Declares a type alias that resolves to a real implementation normally and
+/// to a mock under `cfg(test)`.
+///
+/// This standardizes the conditional compilation needed to swap in mocks for
+/// statically dispatched dependencies.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate pseudo;
+///
+/// struct RealClock;
+/// struct MockClock;
+///
+/// auto!(pub type Clock = RealClock, MockClock);
+///
+/// # fn main() {
+/// // Outside of `cfg(test)`, `Clock` is `RealClock`.
+/// let _clock: Clock = RealClock;
+/// # }
+/// ```
+#[macro_export]
+macro_rules! auto {
+    ($vis:vis type $name:ident = $real:ty, $mock:ty) => {
+        #[cfg(not(test))]
+        $vis type $name = $real;
+
+        #[cfg(test)]
+        $vis type $name = $mock;
+    };
+}
+
+/// Configures a `Mock` to return values based on `match` arms over its
+/// arguments.
+///
+/// This expands to a call to `Mock::use_closure` with a closure that matches
+/// on the arguments, so arms can use any pattern or guard that `match` can.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate pseudo;
+///
+/// use pseudo::Mock;
+///
+/// # fn main() {
+/// let divide = Mock::<(i64, i64), i64>::default();
+///
+/// stub!(divide, {
+///     (1, _) => 10,
+///     (_, 0) => 0,
+///     (x, y) if x % y == 0 => x / y,
+///     _ => 99,
+/// });
+///
+/// assert_eq!(divide.call((1, 0)), 10);
+/// assert_eq!(divide.call((5, 0)), 0);
+/// assert_eq!(divide.call((6, 3)), 2);
+/// assert_eq!(divide.call((7, 3)), 99);
+/// # #[cfg(not(feature = "disabled"))]
+/// assert_eq!(divide.num_calls(), 4);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! stub {
+    ($mock:expr, { $($($pat:pat)|+ $(if $guard:expr)* => $ret:expr),+ $(,)* }) => {
+        $mock.use_closure(Box::new(move |args| match args {
+            $($($pat)|+ $(if $guard)* => $ret),+
+        }))
+    };
+}
+
+/// Panics unless the arguments to a `Mock` are exactly the given ones, in the
+/// same order and with no other calls.
+///
+/// This is equivalent to `Mock::assert_called_with_exactly`, including the
+/// diff in the panic message, but the panic is reported at the location of
+/// the assertion rather than inside `pseudo`.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate pseudo;
+///
+/// use pseudo::Mock;
+///
+/// # fn main() {
+/// let mock = Mock::<&str, ()>::default();
+/// mock.call("first");
+/// mock.call("second");
+///
+/// assert_called_with_exactly!(mock, ["first", "second"]);
+/// # }
+/// ```
+///
+/// ```should_panic
+/// #[macro_use]
+/// extern crate pseudo;
+///
+/// use pseudo::Mock;
+///
+/// # fn main() {
+/// let mock = Mock::<&str, ()>::default();
+/// mock.call("first");
+/// mock.call("third");
+///
+/// // Panics with:
+/// //
+/// //   0: "first"
+/// // - 1: "second"
+/// // + 1: "third"
+/// assert_called_with_exactly!(mock, ["first", "second"]);
+/// # }
+/// ```
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! assert_called_with_exactly {
+    ($mock:expr, $expected:expr $(,)*) => {
+        if let Err(err) = $mock.verify_called_with_exactly(&$expected) {
+            panic!("{}", err);
+        }
+    };
+}
+
+/// Panics unless a `Mock` has been called.
+///
+/// The panic is reported at the location of the assertion. See
+/// `Mock::verify_called` for the fallible equivalent.
+///
+/// # Examples
+///
+/// ```should_panic
+/// #[macro_use]
+/// extern crate pseudo;
+///
+/// use pseudo::Mock;
+///
+/// # fn main() {
+/// let mock = Mock::<i64, ()>::default();
+///
+/// // Panics with: mock was never called
+/// assert_called!(mock);
+/// # }
+/// ```
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! assert_called {
+    ($mock:expr $(,)*) => {
+        if let Err(err) = $mock.verify_called() {
+            panic!("{}", err);
+        }
+    };
+}
+
+/// Panics unless a `Mock` has been called with the given arguments.
+///
+/// On failure, the panic message compares the expected arguments against
+/// each recorded call. The panic is reported at the location of the
+/// assertion. See `Mock::verify_called_with` for the fallible equivalent.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use]
+/// extern crate pseudo;
+///
+/// use pseudo::Mock;
+///
+/// # fn main() {
+/// let mock = Mock::<(&str, u64), ()>::default();
+/// mock.call(("users", 1));
+///
+/// assert_called_with!(mock, ("users", 1));
+/// # }
+/// ```
+///
+/// ```should_panic
+/// #[macro_use]
+/// extern crate pseudo;
+///
+/// use pseudo::Mock;
+///
+/// # fn main() {
+/// let mock = Mock::<(&str, u64), ()>::default();
+/// mock.call(("users", 1));
+/// mock.call(("groups", 2));
+///
+/// // Panics with:
+/// //
+/// // mock was never called with ("users", 2):
+/// // - 0: ("users", 2)
+/// // + 0: ("users", 1)
+/// // - 1: ("users", 2)
+/// // + 1: ("groups", 2)
+/// assert_called_with!(mock, ("users", 2));
+/// # }
+/// ```
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! assert_called_with {
+    ($mock:expr, $args:expr $(,)*) => {
+        if let Err(err) = $mock.verify_called_with($args) {
+            panic!("{}", err);
+        }
+    };
+}
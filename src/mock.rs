@@ -1,8 +1,164 @@
+use std::any::Any;
+#[cfg(not(feature = "disabled"))]
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+#[cfg(not(feature = "disabled"))]
+use std::fmt::Display;
 use std::fmt::{Debug, Formatter};
-use std::sync::{Arc, RwLock};
+#[cfg(not(feature = "disabled"))]
+use std::hash::Hash;
+use std::hash::{BuildHasher, Hasher};
+#[cfg(not(feature = "disabled"))]
+use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(not(feature = "disabled"))]
+use std::sync::mpsc;
+#[cfg(not(feature = "disabled"))]
+use std::sync::mpsc::Sender;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+#[cfg(not(feature = "disabled"))]
+use std::sync::Condvar;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+#[cfg(not(feature = "disabled"))]
+use std::thread::ThreadId;
+use std::time::Duration;
+#[cfg(not(feature = "disabled"))]
+use std::time::Instant;
+
+#[cfg(all(feature = "serde", not(feature = "disabled")))]
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use arg_record::{ArgRecord, CloneArgs};
+use call_record::ReturnSource;
+use context::{with_context, Context};
+use debug_limits::Formatted;
+use drop_tracked::DropTracked;
+use nth_call::NthCall;
+use resolution::{Behavior, Resolution};
+use return_sequence::{Exhaustion, ReturnSequence, Stage, Stages};
+use when::When;
+
+#[cfg(not(feature = "disabled"))]
+use arg_matcher::ArgMatcher;
+#[cfg(not(feature = "disabled"))]
+use call_log::CallLog;
+#[cfg(not(feature = "disabled"))]
+use call_record::CallRecord;
+#[cfg(not(feature = "disabled"))]
+use call_stats::CallStats;
+#[cfg(not(feature = "disabled"))]
+use debug_limits::DebugLimits;
+#[cfg(not(feature = "disabled"))]
+use diff::diff_calls;
+#[cfg(not(feature = "disabled"))]
+use expectation::Expectation;
+#[cfg(not(feature = "disabled"))]
+use sequence::Sequence;
+#[cfg(not(feature = "disabled"))]
+use verification_error::VerificationError;
+#[cfg(not(feature = "disabled"))]
+use weak_mock::WeakMock;
 
 type OptionalRef<T> = Arc<RwLock<Option<T>>>;
+#[cfg(not(feature = "disabled"))]
+type CallObserver<C> = Box<dyn Fn(&C) + Send + Sync>;
+#[cfg(not(feature = "disabled"))]
+type Normalizer<T> = Box<dyn Fn(T) -> T + Send + Sync>;
+pub(crate) type Matcher<C> = Box<dyn Fn(&C) -> bool + Send + Sync>;
+type Stubs<C, R> = Vec<(Matcher<C>, R)>;
+type LazyInit<R> = Box<dyn FnOnce() -> R + Send>;
+type IndexedClosure<C, R> = Box<dyn Fn(usize, C) -> R + Send + Sync>;
+
+/// A behavior that applies to a single call.
+enum OneShot<C, R> {
+    Value(R),
+    Fn(fn(C) -> R),
+    Closure(Box<dyn FnOnce(C) -> R + Send + Sync>),
+}
+
+/// Identifies a call within a `Mock`'s history.
+#[derive(Clone, Copy)]
+struct CallId {
+    /// The zero-based index of the call, counting every call.
+    index: usize,
+    /// The sequence number of the call, if its arguments were recorded.
+    #[cfg(not(feature = "disabled"))]
+    sequence: Option<usize>,
+}
+
+/// The result of a call, which is only cloned if the caller needs to own it.
+enum Response<R> {
+    Owned(R),
+    Shared(Arc<R>),
+}
+
+impl<R> Response<R>
+where
+    R: Clone,
+{
+    fn into_owned(self) -> R {
+        match self {
+            Response::Owned(return_value) => return_value,
+            Response::Shared(return_value) => (*return_value).clone(),
+        }
+    }
+
+    fn into_shared(self) -> Arc<R> {
+        match self {
+            Response::Owned(return_value) => Arc::new(return_value),
+            Response::Shared(return_value) => return_value,
+        }
+    }
+}
+
+/// The sequence number of the next call to any `Mock`, used for ordering
+/// calls across `Mock`s.
+#[cfg(not(feature = "disabled"))]
+static NEXT_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts recorded arguments so that lookups don't scan the call history.
+#[cfg(not(feature = "disabled"))]
+trait CallIndex<T> {
+    fn insert(&mut self, record: &T);
+    fn count(&self, record: &T) -> usize;
+    fn remove(&mut self, record: &T);
+    fn clear(&mut self);
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<T> CallIndex<T> for HashMap<T, usize>
+where
+    T: Clone + Hash + Eq,
+{
+    fn insert(&mut self, record: &T) {
+        *self.entry(record.clone()).or_insert(0) += 1
+    }
+
+    fn count(&self, record: &T) -> usize {
+        self.get(record).cloned().unwrap_or(0)
+    }
+
+    fn remove(&mut self, record: &T) {
+        let remaining = match self.get_mut(record) {
+            Some(count) => {
+                *count -= 1;
+                *count
+            }
+            None => return,
+        };
+
+        if remaining == 0 {
+            HashMap::remove(self, record);
+        }
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+}
 
 /// Used for tracking function call arguments and specifying a predetermined
 /// return value or mock function.
@@ -11,16 +167,96 @@ type OptionalRef<T> = Arc<RwLock<Option<T>>>;
 /// that demonstrate how to use `Mock` for methods that have multiple arguments
 /// as well as methods with argument or return types that do not implement
 /// `Clone`.
-#[derive(Clone)]
-pub struct Mock<C, R>
+///
+/// How call arguments are stored is controlled by the `ArgRecord` policy `A`,
+/// which defaults to storing a clone of every call's arguments. With a policy
+/// that does not clone, such as `DiscardArgs` or `ProjectArgs`, the argument
+/// type does not need to implement `Clone`:
+///
+/// ```
+/// use pseudo::{DiscardArgs, Mock, ProjectArgs};
+///
+/// struct Connection {
+///     port: u16,
+/// }
+///
+/// let open = Mock::<Connection, bool, _>::with_arg_record(true, DiscardArgs);
+/// let port = ProjectArgs(|c: &Connection| c.port);
+/// let close = Mock::<Connection, (), _>::with_arg_record((), port);
+///
+/// assert!(open.call(Connection { port: 80 }));
+/// close.call(Connection { port: 80 });
+///
+/// # #[cfg(not(feature = "disabled"))]
+/// assert_eq!(open.num_calls(), 1);
+/// # #[cfg(not(feature = "disabled"))]
+/// assert!(close.called_with(80u16));
+/// ```
+pub struct Mock<C, R, A = CloneArgs>
 where
-    C: Clone,
     R: Clone,
+    A: ArgRecord<C>,
 {
-    return_value: Arc<RwLock<R>>,
+    return_value: OptionalRef<Arc<R>>,
+    initial_return_value: OptionalRef<Arc<R>>,
+    lazy_init: Arc<Mutex<Option<LazyInit<R>>>>,
     mock_fn: OptionalRef<fn(C) -> R>,
-    mock_closure: OptionalRef<Box<Fn(C) -> R + Send + Sync>>,
-    calls: Arc<RwLock<Vec<C>>>,
+    mock_closure: OptionalRef<Box<dyn Fn(C) -> R + Send + Sync>>,
+    mock_indexed_closure: OptionalRef<IndexedClosure<C, R>>,
+    delegate: OptionalRef<Box<dyn Fn(C) -> R + Send + Sync>>,
+    delegating: Arc<AtomicBool>,
+    sequence: OptionalRef<ReturnSequence<R>>,
+    once: Arc<RwLock<VecDeque<OneShot<C, R>>>>,
+    nth: Arc<RwLock<HashMap<usize, R>>>,
+    overrides: Arc<AtomicUsize>,
+    stubs: Arc<RwLock<Stubs<C, R>>>,
+    strict: OptionalRef<fn(&C) -> String>,
+    resolution: Arc<RwLock<Resolution>>,
+    configured: Arc<RwLock<Arc<Vec<Behavior>>>>,
+    context: OptionalRef<Context>,
+    delay: OptionalRef<(Duration, Duration)>,
+    rng: Arc<Mutex<XorShift>>,
+    #[cfg(not(feature = "disabled"))]
+    name: OptionalRef<String>,
+    arg_record: A,
+    #[cfg(not(feature = "disabled"))]
+    calls: Arc<RwLock<Vec<A::Record>>>,
+    num_calls: Arc<AtomicUsize>,
+    #[cfg(not(feature = "disabled"))]
+    call_signal: Arc<(Mutex<()>, Condvar)>,
+    #[cfg(not(feature = "disabled"))]
+    waiters: Arc<AtomicUsize>,
+    #[cfg(not(feature = "disabled"))]
+    recording_disabled: Arc<AtomicBool>,
+    #[cfg(not(feature = "disabled"))]
+    sample_every: Arc<RwLock<usize>>,
+    #[cfg(not(feature = "disabled"))]
+    history_capacity: OptionalRef<usize>,
+    #[cfg(not(feature = "disabled"))]
+    call_index: OptionalRef<Box<dyn CallIndex<A::Record> + Send + Sync>>,
+    #[cfg(not(feature = "disabled"))]
+    timestamps: OptionalRef<Vec<Instant>>,
+    #[cfg(not(feature = "disabled"))]
+    sequences: Arc<RwLock<Vec<usize>>>,
+    #[cfg(not(feature = "disabled"))]
+    threads: Arc<RwLock<Vec<ThreadId>>>,
+    #[cfg(not(feature = "disabled"))]
+    sources: Arc<RwLock<HashMap<usize, ReturnSource>>>,
+    #[cfg(not(feature = "disabled"))]
+    returned: OptionalRef<HashMap<usize, R>>,
+    #[cfg(not(feature = "disabled"))]
+    expectations: Arc<RwLock<Vec<Expectation<A::Record>>>>,
+    #[cfg(not(feature = "disabled"))]
+    call_log: OptionalRef<CallObserver<C>>,
+    #[cfg(not(feature = "disabled"))]
+    observers: Arc<RwLock<Vec<CallObserver<C>>>>,
+    #[cfg(not(feature = "disabled"))]
+    observed: Arc<AtomicBool>,
+    #[cfg(not(feature = "disabled"))]
+    normalizer: OptionalRef<Normalizer<A::Record>>,
+    #[cfg(not(feature = "disabled"))]
+    debug_limits: Arc<RwLock<DebugLimits>>,
+    return_refs: Arc<RwLock<HashMap<usize, Arc<R>>>>,
 }
 
 impl<C, R> Mock<C, R>
@@ -30,11 +266,123 @@ where
 {
     /// Creates a new `Mock` that will return `return_value`.
     pub fn new<T: Into<R>>(return_value: T) -> Self {
+        Self::with_arg_record(return_value, CloneArgs)
+    }
+
+    /// Creates a new `Mock` that will return the output of `init`, which is
+    /// only called the first time the return value is needed.
+    ///
+    /// This avoids constructing expensive return values for mocks that are
+    /// never called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let built = Arc::new(AtomicBool::new(false));
+    /// let mock = Mock::<(), Vec<u8>>::new_lazy({
+    ///     let built = built.clone();
+    ///     move || {
+    ///         built.store(true, Ordering::SeqCst);
+    ///         vec![0; 1 << 20]
+    ///     }
+    /// });
+    ///
+    /// assert!(!built.load(Ordering::SeqCst));
+    /// assert_eq!(mock.call(()).len(), 1 << 20);
+    /// assert!(built.load(Ordering::SeqCst));
+    /// ```
+    pub fn new_lazy<F>(init: F) -> Self
+    where
+        F: FnOnce() -> R + Send + 'static,
+    {
+        let mock = Self::with_optional_return_value(None, CloneArgs);
+        *mock.lazy_init.lock().unwrap() = Some(Box::new(init));
+        mock
+    }
+}
+
+impl<C, R, A> Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    /// Creates a new `Mock` that will return `return_value` and store call
+    /// arguments according to `arg_record`.
+    ///
+    /// See `ArgRecord` for the available policies.
+    pub fn with_arg_record<T: Into<R>>(return_value: T, arg_record: A) -> Self {
+        Self::with_optional_return_value(Some(return_value.into()), arg_record)
+    }
+
+    fn with_optional_return_value(return_value: Option<R>, arg_record: A) -> Self {
+        let return_value = return_value.map(Arc::new);
+
         Mock {
-            return_value: Arc::new(RwLock::new(return_value.into())),
+            return_value: Arc::new(RwLock::new(return_value.clone())),
+            initial_return_value: Arc::new(RwLock::new(return_value)),
+            lazy_init: Arc::new(Mutex::new(None)),
             mock_fn: Arc::new(RwLock::new(None)),
             mock_closure: Arc::new(RwLock::new(None)),
+            mock_indexed_closure: Arc::new(RwLock::new(None)),
+            delegate: Arc::new(RwLock::new(None)),
+            delegating: Arc::new(AtomicBool::new(false)),
+            sequence: Arc::new(RwLock::new(None)),
+            once: Arc::new(RwLock::new(VecDeque::new())),
+            nth: Arc::new(RwLock::new(HashMap::new())),
+            overrides: Arc::new(AtomicUsize::new(0)),
+            stubs: Arc::new(RwLock::new(vec![])),
+            strict: Arc::new(RwLock::new(None)),
+            resolution: Arc::new(RwLock::new(Resolution::default())),
+            configured: Arc::new(RwLock::new(Arc::new(vec![]))),
+            context: Arc::new(RwLock::new(None)),
+            delay: Arc::new(RwLock::new(None)),
+            rng: Arc::new(Mutex::new(XorShift::seeded())),
+            #[cfg(not(feature = "disabled"))]
+            name: Arc::new(RwLock::new(None)),
+            arg_record,
+            #[cfg(not(feature = "disabled"))]
             calls: Arc::new(RwLock::new(vec![])),
+            num_calls: Arc::new(AtomicUsize::new(0)),
+            #[cfg(not(feature = "disabled"))]
+            call_signal: Arc::new((Mutex::new(()), Condvar::new())),
+            #[cfg(not(feature = "disabled"))]
+            waiters: Arc::new(AtomicUsize::new(0)),
+            #[cfg(not(feature = "disabled"))]
+            recording_disabled: Arc::new(AtomicBool::new(false)),
+            #[cfg(not(feature = "disabled"))]
+            sample_every: Arc::new(RwLock::new(1)),
+            #[cfg(not(feature = "disabled"))]
+            history_capacity: Arc::new(RwLock::new(None)),
+            #[cfg(not(feature = "disabled"))]
+            call_index: Arc::new(RwLock::new(None)),
+            #[cfg(not(feature = "disabled"))]
+            timestamps: Arc::new(RwLock::new(None)),
+            #[cfg(not(feature = "disabled"))]
+            sequences: Arc::new(RwLock::new(vec![])),
+            #[cfg(not(feature = "disabled"))]
+            threads: Arc::new(RwLock::new(vec![])),
+            #[cfg(not(feature = "disabled"))]
+            sources: Arc::new(RwLock::new(HashMap::new())),
+            #[cfg(not(feature = "disabled"))]
+            returned: Arc::new(RwLock::new(None)),
+            #[cfg(not(feature = "disabled"))]
+            expectations: Arc::new(RwLock::new(vec![])),
+            #[cfg(not(feature = "disabled"))]
+            call_log: Arc::new(RwLock::new(None)),
+            #[cfg(not(feature = "disabled"))]
+            observers: Arc::new(RwLock::new(vec![])),
+            #[cfg(not(feature = "disabled"))]
+            observed: Arc::new(AtomicBool::new(false)),
+            #[cfg(not(feature = "disabled"))]
+            normalizer: Arc::new(RwLock::new(None)),
+            #[cfg(not(feature = "disabled"))]
+            debug_limits: Arc::new(RwLock::new(DebugLimits::default())),
+            return_refs: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -44,9 +392,21 @@ where
     ///
     /// * the return value specified at construction time
     /// * the return value specified via `Mock::return_value` or a derivative,
-    /// such as `Mock::return_some`
+    ///   such as `Mock::return_some`
     /// * the output of the function set via `Mock::use_fn` with the current arguments
     /// * the output of the closure set via `Mock::use_closure` with the current arguments
+    /// * the output of the closure set via `Mock::use_indexed_closure` with the
+    ///   index of the call and the current arguments
+    /// * the next value of the sequence set via `Mock::return_values` or
+    ///   `Mock::use_iterator`
+    /// * the return value set for the current arguments via
+    ///   `Mock::return_value_for` or `Mock::when`
+    ///
+    /// Values set via `Mock::on_nth_call`, `Mock::return_value_once` or one
+    /// of its derivatives, such as `Mock::use_fn_once`, take precedence over
+    /// all of these. Next, calls are forwarded to the function set via
+    /// `Mock::delegate_to`, if delegating. Otherwise, the most specific
+    /// behavior wins; use `Mock::resolve_with` to change this.
     ///
     /// # Examples
     ///
@@ -68,18 +428,90 @@ where
     /// mock.use_fn(str::trim);
     /// assert_eq!(mock.call("  test  "), "test");
     /// ```
+    #[inline]
     pub fn call(&self, args: C) -> R {
-        self.calls.write().unwrap().push(args.clone());
+        let (call, response) = self.answer(args);
+        let return_value = response.into_owned();
+        self.record_return(call, &return_value);
+        return_value
+    }
+
+    /// Use the `Mock` like `Mock::call`, but return the value set via
+    /// `Mock::return_shared` or `Mock::return_value` without cloning it.
+    ///
+    /// Return values from other behaviors, such as a function set via
+    /// `Mock::use_fn`, are wrapped in a new `Arc`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let payload = Arc::new(vec![0u8; 4 * 1024 * 1024]);
+    /// let read = Mock::<u64, Vec<u8>>::default();
+    /// read.return_shared(payload.clone());
+    ///
+    /// assert!(Arc::ptr_eq(&read.call_shared(0), &payload));
+    /// assert!(Arc::ptr_eq(&read.call_shared(1), &payload));
+    /// # #[cfg(not(feature = "disabled"))]
+    /// assert_eq!(read.num_calls(), 2);
+    /// ```
+    pub fn call_shared(&self, args: C) -> Arc<R> {
+        let (call, response) = self.answer(args);
+        let return_value = response.into_shared();
+        self.record_return(call, &return_value);
+        return_value
+    }
+
+    fn answer(&self, args: C) -> (CallId, Response<R>) {
+        let call = self.record_call(&args);
+        self.notify_call(&args);
+        self.sleep();
+
+        if let Some((once, source)) = self.take_override(call) {
+            self.served(call, source);
 
-        if let Some(ref mock_fn) = *self.mock_fn.read().unwrap() {
-            return mock_fn(args);
+            let return_value = match once {
+                OneShot::Value(return_value) => return_value,
+                OneShot::Fn(mock_fn) => with_context(self.context(), || mock_fn(args)),
+                OneShot::Closure(mock_closure) => {
+                    with_context(self.context(), || mock_closure(args))
+                }
+            };
+
+            return (call, Response::Owned(return_value));
+        }
+
+        if self.delegating.load(Ordering::SeqCst) {
+            if let Some(ref delegate) = *self.delegate.read().unwrap() {
+                self.served(call, ReturnSource::Delegate);
+                let return_value = with_context(self.context(), || delegate(args));
+                return (call, Response::Owned(return_value));
+            }
         }
 
-        if let Some(ref mock_closure) = *self.mock_closure.read().unwrap() {
-            return mock_closure(args);
+        if let Some(debug_args) = *self.strict.read().unwrap() {
+            let expected = self
+                .stubs
+                .read()
+                .unwrap()
+                .iter()
+                .any(|(matches, _)| matches(&args));
+
+            if !expected {
+                panic!(
+                    "mock was called with unexpected arguments {}",
+                    debug_args(&args)
+                );
+            }
         }
 
-        self.return_value.read().unwrap().clone()
+        // Only the `Arc` is cloned, so that `configure` can still be called
+        // while responding, e.g. from a closure set via `use_closure`.
+        let configured = Arc::clone(&self.configured.read().unwrap());
+        (call, self.respond(args, call, &configured))
     }
 
     /// Override the initial return value.
@@ -95,8 +527,209 @@ where
     /// assert_eq!(mock.call("something"), "new value");
     /// ```
     pub fn return_value<T: Into<R>>(&self, return_value: T) {
+        self.return_shared(Arc::new(return_value.into()))
+    }
+
+    /// Override the return value with one that is shared rather than cloned
+    /// by `Mock::call_shared`.
+    ///
+    /// `Mock::call` still returns a clone of the shared value. See
+    /// `Mock::call_shared` for more details.
+    pub fn return_shared(&self, return_value: Arc<R>) {
         let mut value = self.return_value.write().unwrap();
-        *value = return_value.into()
+        *value = Some(return_value);
+        self.configure(Behavior::Value)
+    }
+
+    /// Returns a clone of the current return value.
+    ///
+    /// When the return value is itself a mock, or a struct of mocks, the
+    /// clone shares its state with the value returned by `Mock::call`. This
+    /// allows chained calls to be stubbed and verified from the root `Mock`,
+    /// with the whole tree of mocks created by `Default`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// #[derive(Clone, Default)]
+    /// struct MockClient {
+    ///     send: Mock<String, bool>,
+    /// }
+    ///
+    /// #[derive(Clone, Default)]
+    /// struct MockService {
+    ///     client: Mock<(), MockClient>,
+    /// }
+    ///
+    /// let service = Mock::<(), MockService>::default();
+    /// service.returns().client.returns().send.return_value(true);
+    ///
+    /// let sent = service.call(()).client.call(()).send.call("x".to_string());
+    ///
+    /// assert!(sent);
+    /// # #[cfg(not(feature = "disabled"))]
+    /// assert!(service.returns().client.returns().send.called_with("x"));
+    /// ```
+    pub fn returns(&self) -> R {
+        self.with_return_value(R::clone)
+    }
+
+    /// Return `return_value` from the next call only, then revert to the
+    /// previously configured behavior.
+    ///
+    /// Calling this several times queues up values for successive calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<(), Result<u64, &str>>::new(Ok(1));
+    /// mock.return_value_once(Err("transient failure"));
+    ///
+    /// assert_eq!(mock.call(()), Err("transient failure"));
+    /// assert_eq!(mock.call(()), Ok(1));
+    /// assert_eq!(mock.call(()), Ok(1));
+    /// ```
+    pub fn return_value_once<T: Into<R>>(&self, return_value: T) {
+        self.push_once(OneShot::Value(return_value.into()))
+    }
+
+    /// Override what the `Mock` does on the `n`th call, counting from 0,
+    /// while leaving all other calls on the configured behavior.
+    ///
+    /// Calls are counted from construction or the last `Mock::reset_calls`.
+    /// See `NthCall` for more details.
+    pub fn on_nth_call<'a>(&'a self, n: usize) -> NthCall<'a, C, R, A> {
+        NthCall::new(self, n)
+    }
+
+    pub(crate) fn set_nth_return_value(&self, n: usize, return_value: R) {
+        if self.nth.write().unwrap().insert(n, return_value).is_none() {
+            self.overrides.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Start adding a behavior that applies whenever the arguments to
+    /// `Mock::call` satisfy `predicate`.
+    ///
+    /// See `When` for more details.
+    pub fn when<'a, F>(&'a self, predicate: F) -> When<'a, C, R, A>
+    where
+        F: Fn(&C) -> bool + Send + Sync + 'static,
+    {
+        When::new(self, Box::new(predicate))
+    }
+
+    /// Return each of `return_values` in order on successive calls, then
+    /// keep returning the last one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<(), u64>::default();
+    /// mock.return_values(vec![1, 2, 3]);
+    ///
+    /// assert_eq!(mock.call(()), 1);
+    /// assert_eq!(mock.call(()), 2);
+    /// assert_eq!(mock.call(()), 3);
+    /// assert_eq!(mock.call(()), 3);
+    /// ```
+    pub fn return_values(&self, return_values: Vec<R>) {
+        self.return_values_then(return_values, Exhaustion::RepeatLast)
+    }
+
+    /// Return `before` from the next `n` calls, then `after` from every call
+    /// after that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let has_quota = Mock::<(), bool>::default();
+    /// has_quota.switch_after(1, true, false);
+    ///
+    /// assert!(has_quota.call(()));
+    /// assert!(!has_quota.call(()));
+    /// assert!(!has_quota.call(()));
+    /// ```
+    pub fn switch_after(&self, n: usize, before: R, after: R) {
+        let mut return_values = vec![before; n];
+        return_values.push(after);
+        self.return_values(return_values)
+    }
+
+    /// Return each of `return_values` in order on successive calls, starting
+    /// again from the first one once they have all been used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let next_shard = Mock::<(), &str>::default();
+    /// next_shard.return_values_cycling(vec!["a", "b"]);
+    ///
+    /// assert_eq!(next_shard.call(()), "a");
+    /// assert_eq!(next_shard.call(()), "b");
+    /// assert_eq!(next_shard.call(()), "a");
+    /// assert_eq!(next_shard.call(()), "b");
+    /// ```
+    pub fn return_values_cycling(&self, return_values: Vec<R>) {
+        self.return_values_then(return_values, Exhaustion::Cycle)
+    }
+
+    /// Return each of `return_values` in order on successive calls, then
+    /// behave according to `exhaustion`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::{Exhaustion, Mock};
+    ///
+    /// let mock = Mock::<(), &str>::new("default");
+    /// mock.return_values_then(vec!["first"], Exhaustion::Fallback);
+    ///
+    /// assert_eq!(mock.call(()), "first");
+    /// assert_eq!(mock.call(()), "default");
+    /// ```
+    ///
+    /// ```should_panic
+    /// use pseudo::{Exhaustion, Mock};
+    ///
+    /// let mock = Mock::<(), u64>::default();
+    /// mock.return_values_then(vec![1, 2], Exhaustion::Panic);
+    ///
+    /// mock.call(());
+    /// mock.call(());
+    ///
+    /// // Panics, since the sequence was exhausted after 2 calls
+    /// mock.call(());
+    /// ```
+    pub fn return_values_then(&self, return_values: Vec<R>, exhaustion: Exhaustion) {
+        *self.sequence.write().unwrap() = Some(ReturnSequence::new(return_values, exhaustion));
+        self.configure(Behavior::Sequence)
+    }
+
+    /// Return `return_value` from the next call, starting a sequence of
+    /// stages that can be extended with `Stages::then_returns` and
+    /// `Stages::then_panics`.
+    ///
+    /// See `Stages` for more details.
+    pub fn first_returns<'a>(&'a self, return_value: R) -> Stages<'a, C, R, A> {
+        self.return_values(vec![return_value]);
+        Stages::new(self)
+    }
+
+    pub(crate) fn push_stage(&self, stage: Stage<R>) {
+        if let Some(ref mut sequence) = *self.sequence.write().unwrap() {
+            sequence.push(stage)
+        }
     }
 
     /// Specify a function to determine the `Mock`'s return value based on
@@ -138,9 +771,11 @@ where
     pub fn use_fn(&self, mock_fn: fn(C) -> R) {
         let mut closure_value = self.mock_closure.write().unwrap();
         *closure_value = None;
+        *self.mock_indexed_closure.write().unwrap() = None;
 
         let mut fn_value = self.mock_fn.write().unwrap();
-        *fn_value = Some(mock_fn)
+        *fn_value = Some(mock_fn);
+        self.configure(Behavior::Function)
     }
 
     /// Specify a closure to determine the `Mock`'s return value based on
@@ -173,156 +808,3480 @@ where
     /// assert_eq!(mock.call((1, 1, 1)), 3);
     /// assert_eq!(mock.call((1, 2, 3,)), 6);
     /// ```
-    pub fn use_closure(&self, mock_fn: Box<Fn(C) -> R + Send + Sync>) {
+    pub fn use_closure(&self, mock_fn: Box<dyn Fn(C) -> R + Send + Sync>) {
         let mut fn_value = self.mock_fn.write().unwrap();
         *fn_value = None;
+        *self.mock_indexed_closure.write().unwrap() = None;
 
         let mut closure_value = self.mock_closure.write().unwrap();
-        *closure_value = Some(mock_fn)
+        *closure_value = Some(mock_fn);
+        self.configure(Behavior::Function)
     }
 
-    /// Returns true if `Mock::call` has been called.
+    /// Specify a closure to determine the `Mock`'s return value based on
+    /// the zero-based index of the call as well as its arguments.
+    ///
+    /// The index counts every call to `Mock::call`, including those that
+    /// were answered by another behavior, and restarts from zero after
+    /// `Mock::reset_calls`.
     ///
     /// # Examples
     ///
     /// ```
     /// use pseudo::Mock;
     ///
-    /// let mock = Mock::<i64, ()>::default();
-    ///
-    /// assert!(!mock.called());
-    ///
-    /// // mock.call(10);
+    /// let mock = Mock::<&str, String>::default();
+    /// mock.use_indexed_closure(Box::new(|index, name| match index {
+    ///     0 => format!("hello, {}", name),
+    ///     _ => format!("welcome back, {}", name),
+    /// }));
     ///
-    /// // assert!(mock.called());
+    /// assert_eq!(mock.call("ada"), "hello, ada");
+    /// assert_eq!(mock.call("ada"), "welcome back, ada");
     /// ```
-    pub fn called(&self) -> bool {
-        let calls = (*self.calls).read().unwrap();
-        !calls.is_empty()
+    pub fn use_indexed_closure(&self, mock_fn: Box<dyn Fn(usize, C) -> R + Send + Sync>) {
+        *self.mock_fn.write().unwrap() = None;
+        *self.mock_closure.write().unwrap() = None;
+        *self.mock_indexed_closure.write().unwrap() = Some(mock_fn);
+        self.configure(Behavior::Function)
     }
 
-    /// Returns the number of times `Mock::call` has been called.
+    /// Use `mock_fn` to determine the return value of the next call only,
+    /// then revert to the previously configured behavior.
+    ///
+    /// Like `Mock::return_value_once`, calling this several times queues up
+    /// behaviors for successive calls.
     ///
     /// # Examples
     ///
     /// ```
     /// use pseudo::Mock;
     ///
-    /// let mock = Mock::<i64, i64>::new(0);
+    /// let mock = Mock::<u64, Result<u64, String>>::new(Ok(0));
+    /// mock.use_fn_once(|x| Err(format!("cannot process {}", x)));
     ///
-    /// assert_eq!(mock.num_calls(), 0);
-    /// mock.call(5);
-    /// assert_eq!(mock.num_calls(), 1);
-    /// mock.call(10);
-    /// assert_eq!(mock.num_calls(), 2);
+    /// assert_eq!(mock.call(3), Err("cannot process 3".to_string()));
+    /// assert_eq!(mock.call(4), Ok(0));
     /// ```
-    pub fn num_calls(&self) -> usize {
-        self.calls.read().unwrap().len()
+    pub fn use_fn_once(&self, mock_fn: fn(C) -> R) {
+        self.push_once(OneShot::Fn(mock_fn))
     }
 
-    /// Returns the arguments to `Mock::call` in order from first to last.
+    /// Use `mock_fn` to determine the return value of the next call only,
+    /// then revert to the previously configured behavior.
+    ///
+    /// Since the closure is only called once, it may move out of the values
+    /// it captures.
     ///
     /// # Examples
     ///
     /// ```
     /// use pseudo::Mock;
     ///
-    /// let mock = Mock::<&str, &str>::new("");
-    ///
-    /// mock.call("first");
-    /// mock.call("second");
-    /// mock.call("third");
+    /// let mock = Mock::<u64, Vec<u64>>::default();
+    /// let batch = vec![1, 2, 3];
+    /// mock.use_closure_once(Box::new(move |x| {
+    ///     let mut batch = batch;
+    ///     batch.push(x);
+    ///     batch
+    /// }));
     ///
-    /// assert_eq!(mock.calls().as_slice(), ["first", "second", "third"]);
+    /// assert_eq!(mock.call(4), vec![1, 2, 3, 4]);
+    /// assert_eq!(mock.call(5), Vec::<u64>::new());
     /// ```
-    pub fn calls(&self) -> Vec<C> {
-        self.calls.read().unwrap().clone()
+    pub fn use_closure_once(&self, mock_fn: Box<dyn FnOnce(C) -> R + Send + Sync>) {
+        self.push_once(OneShot::Closure(mock_fn))
     }
 
-    /// Reset the call history for the `Mock`.
+    /// Attach `context` to the `Mock`, so that functions and closures set via
+    /// `Mock::use_fn` or `Mock::use_closure` can read it with
+    /// `pseudo::context` while they run.
     ///
-    /// # Examples
+    /// Clones of the `Mock` share the context.
+    pub fn set_context<T>(&self, context: T)
+    where
+        T: Any + Send + Sync,
+    {
+        *self.context.write().unwrap() = Some(Arc::new(context))
+    }
+
+    /// Use `resolution` to choose between the return value and a function or
+    /// closure, when both have been configured.
     ///
-    /// ```
-    /// use pseudo::Mock;
+    /// See `Resolution` for the available policies.
     ///
-    /// let mock = Mock::<&str, &str>::default();
+    /// # Examples
     ///
-    /// mock.call("first");
-    /// mock.call("second");
+    /// ```should_panic
+    /// use pseudo::{Mock, Resolution};
     ///
-    /// assert!(mock.called());
-    /// assert_eq!(mock.num_calls(), 2);
-    /// assert!(mock.called_with("first"));
-    /// assert!(mock.called_with("second"));
+    /// let mock = Mock::<i64, i64>::default();
+    /// mock.resolve_with(Resolution::PanicOnAmbiguity);
     ///
-    /// mock.reset_calls();
+    /// mock.use_fn(|x| x + 1);
+    /// mock.return_value(10);
     ///
-    /// assert!(!mock.called());
-    /// assert_eq!(mock.num_calls(), 0);
-    /// assert!(!mock.called_with("first"));
-    /// assert!(!mock.called_with("second"));
+    /// // Panics, since it is unclear which behavior the test intended
+    /// mock.call(1);
     /// ```
-    pub fn reset_calls(&self) {
-        self.calls.write().unwrap().clear()
+    pub fn resolve_with(&self, resolution: Resolution) {
+        *self.resolution.write().unwrap() = resolution
     }
-}
 
-impl<C, R> Default for Mock<C, R>
-where
-    C: Clone,
-    R: Clone + Default,
-{
-    /// Use `R::default()` as the initial return value.
+    /// Make every call to `Mock::call` sleep for `delay` before returning,
+    /// whichever behavior determines its return value.
+    ///
+    /// The call is recorded before sleeping, so other threads can observe it
+    /// while it is in progress.
     ///
     /// # Examples
     ///
     /// ```
+    /// use std::time::{Duration, Instant};
+    ///
     /// use pseudo::Mock;
     ///
-    /// let mock = Mock::<i64, i64>::default();
-    /// assert_eq!(mock.call(10), 0);
+    /// let fetch = Mock::<&str, &str>::new("body");
+    /// fetch.with_delay(Duration::from_millis(20));
     ///
-    /// let mock = Mock::<(), String>::default();
-    /// assert_eq!(&mock.call(()), "");
+    /// let start = Instant::now();
     ///
-    /// let mock = Mock::<(i64, &str), Option<bool>>::default();
-    /// assert_eq!(mock.call((10, "test")), None);
+    /// assert_eq!(fetch.call("/"), "body");
+    /// assert!(start.elapsed() >= Duration::from_millis(20));
     /// ```
-    fn default() -> Self {
-        Self::new(R::default())
+    pub fn with_delay(&self, delay: Duration) {
+        self.with_jittered_delay(delay, delay)
     }
-}
 
-impl<C, R> Mock<C, R>
-where
-    C: Clone + PartialEq,
-    R: Clone,
-{
-    /// Returns true if the specified argument has been used for `Mock::call`.
+    /// Make every call to `Mock::call` sleep for a random duration between
+    /// `min` and `max` before returning.
     ///
     /// # Examples
     ///
     /// ```
+    /// use std::time::{Duration, Instant};
+    ///
     /// use pseudo::Mock;
     ///
-    /// let mock = Mock::<&str, ()>::new(());
-    /// mock.call("foo");
-    /// mock.call("bar");
+    /// let fetch = Mock::<&str, &str>::new("body");
+    /// fetch.with_jittered_delay(Duration::from_millis(10), Duration::from_millis(30));
     ///
-    /// assert!(mock.called_with("foo"));
-    /// assert!(mock.called_with("bar"));
-    /// assert!(!mock.called_with("baz"));
+    /// for _ in 0..5 {
+    ///     let start = Instant::now();
+    ///     fetch.call("/");
+    ///     let elapsed = start.elapsed();
+    ///
+    ///     assert!(elapsed >= Duration::from_millis(10));
+    ///     // Sleeping may overshoot, so leave some slack above `max`.
+    ///     assert!(elapsed < Duration::from_millis(30) + Duration::from_secs(1));
+    /// }
     /// ```
-    pub fn called_with<T: Into<C>>(&self, args: T) -> bool {
-        self.calls.read().unwrap().contains(&args.into())
+    pub fn with_jittered_delay(&self, min: Duration, max: Duration) {
+        *self.delay.write().unwrap() = Some((min, max))
     }
-}
 
-impl<C, S> Mock<C, Option<S>>
-where
-    C: Clone,
+    /// Register `observer` to be invoked with the arguments of every call to
+    /// `Mock::call`, without affecting its return value.
+    ///
+    /// Observers run in the order they were registered, before the return
+    /// value is determined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::mpsc;
+    /// use std::sync::Mutex;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let (tx, rx) = mpsc::channel();
+    /// let tx = Mutex::new(tx);
+    ///
+    /// let publish = Mock::<&str, bool>::new(true);
+    /// publish.on_call(Box::new(move |topic| tx.lock().unwrap().send(*topic).unwrap()));
+    ///
+    /// assert!(publish.call("orders"));
+    /// assert_eq!(rx.recv(), Ok("orders"));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn on_call(&self, observer: Box<dyn Fn(&C) + Send + Sync>) {
+        self.observers.write().unwrap().push(observer);
+        self.observed.store(true, Ordering::SeqCst)
+    }
+
+    /// Join `seq` as its next member, so that `Sequence::verify` checks that
+    /// this `Mock` is called after the members that joined before it and
+    /// before those that join after it.
+    ///
+    /// Calls are added to `seq` whether or not they are being recorded. See
+    /// `Sequence` for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::{Mock, Sequence};
+    ///
+    /// let seq = Sequence::new();
+    /// let open = Mock::<&str, ()>::default();
+    /// let read = Mock::<usize, Vec<u8>>::default();
+    ///
+    /// open.in_sequence(&seq);
+    /// read.in_sequence(&seq);
+    ///
+    /// read.call(16);
+    /// open.call("data.bin");
+    ///
+    /// assert!(!seq.called_in_order());
+    /// assert_eq!(
+    ///     seq.verify().unwrap_err().actual,
+    ///     vec!["mock #2".to_string(), "mock #1".to_string()]
+    /// );
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn in_sequence(&self, seq: &Sequence) {
+        let member = seq.join(self.name.clone());
+        let seq = seq.clone();
+
+        self.on_call(Box::new(move |_| seq.push(member)))
+    }
+
+    /// Forward calls to `delegate`, such as the real implementation of the
+    /// mocked function, while still recording them.
+    ///
+    /// While delegating, the `Mock`'s other behaviors are ignored, except for
+    /// those set via `Mock::on_nth_call` or `Mock::return_value_once` and its
+    /// derivatives, which still take precedence. Use `Mock::set_delegating`
+    /// to switch between forwarding calls and stubbing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// fn parse(input: &str) -> Option<u64> {
+    ///     input.parse().ok()
+    /// }
+    ///
+    /// let mock = Mock::<&str, Option<u64>>::default();
+    /// mock.delegate_to(Box::new(parse));
+    /// mock.return_value_once(None);
+    ///
+    /// assert_eq!(mock.call("1"), None);
+    /// assert_eq!(mock.call("2"), Some(2));
+    /// # #[cfg(not(feature = "disabled"))]
+    /// assert_eq!(mock.calls(), vec!["1", "2"]);
+    /// ```
+    pub fn delegate_to(&self, delegate: Box<dyn Fn(C) -> R + Send + Sync>) {
+        *self.delegate.write().unwrap() = Some(delegate);
+        self.set_delegating(true)
+    }
+
+    /// Switch between forwarding calls to the function set via
+    /// `Mock::delegate_to` and using the `Mock`'s other behaviors.
+    ///
+    /// Has no effect on `Mock::call` if no function has been set via
+    /// `Mock::delegate_to`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, i64>::new(0);
+    /// mock.delegate_to(Box::new(|x| x.abs()));
+    ///
+    /// assert_eq!(mock.call(-3), 3);
+    ///
+    /// mock.set_delegating(false);
+    /// assert_eq!(mock.call(-3), 0);
+    ///
+    /// mock.set_delegating(true);
+    /// assert_eq!(mock.call(-3), 3);
+    /// ```
+    pub fn set_delegating(&self, delegating: bool) {
+        self.delegating.store(delegating, Ordering::SeqCst)
+    }
+
+    /// Returns true if `Mock::call` has been called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    ///
+    /// assert!(!mock.called());
+    ///
+    /// // mock.call(10);
+    ///
+    /// // assert!(mock.called());
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn called(&self) -> bool {
+        self.num_calls() > 0
+    }
+
+    /// Returns true if `Mock::call` has been called exactly once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    /// assert!(!mock.called_once());
+    ///
+    /// mock.call(1);
+    /// assert!(mock.called_once());
+    ///
+    /// mock.call(2);
+    /// assert!(!mock.called_once());
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn called_once(&self) -> bool {
+        self.num_calls() == 1
+    }
+
+    /// Returns true if `Mock::call` has been called at least `n` times.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    /// mock.call(1);
+    /// mock.call(2);
+    ///
+    /// assert!(mock.called_at_least(2));
+    /// assert!(!mock.called_at_least(3));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn called_at_least(&self, n: usize) -> bool {
+        self.num_calls() >= n
+    }
+
+    /// Returns true if `Mock::call` has been called at most `n` times.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let backend = Mock::<&str, &str>::new("value");
+    /// backend.call("key");
+    ///
+    /// assert!(backend.called_at_most(1));
+    ///
+    /// backend.call("key");
+    ///
+    /// assert!(!backend.called_at_most(1));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn called_at_most(&self, n: usize) -> bool {
+        self.num_calls() <= n
+    }
+
+    /// Blocks until `Mock::call` has been called, or until `timeout` has
+    /// elapsed. Returns true if the `Mock` was called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let flush = Mock::<(), ()>::default();
+    ///
+    /// assert!(!flush.wait_called(Duration::from_millis(10)));
+    ///
+    /// {
+    ///     let flush = flush.clone();
+    ///     thread::spawn(move || flush.call(()));
+    /// }
+    ///
+    /// assert!(flush.wait_called(Duration::from_secs(10)));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn wait_called(&self, timeout: Duration) -> bool {
+        self.wait_until(timeout, || self.called())
+    }
+
+    /// Expect `Mock::call` to be called exactly `n` times, as checked by
+    /// `Mock::verify`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    /// mock.expect_calls(2);
+    ///
+    /// mock.call(1);
+    /// assert!(mock.verify().is_err());
+    ///
+    /// mock.call(2);
+    /// assert!(mock.verify().is_ok());
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn expect_calls(&self, n: usize) {
+        self.expect(Expectation::Calls(n))
+    }
+
+    /// Expect `Mock::call` to be called at least `n` times, as checked by
+    /// `Mock::verify`.
+    #[cfg(not(feature = "disabled"))]
+    pub fn expect_at_least(&self, n: usize) {
+        self.expect(Expectation::AtLeast(n))
+    }
+
+    /// Expect `Mock::call` to be called at most `n` times, as checked by
+    /// `Mock::verify`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let backend = Mock::<&str, &str>::new("value");
+    /// backend.expect_at_least(1);
+    /// backend.expect_at_most(1);
+    ///
+    /// backend.call("key");
+    /// assert!(backend.verify().is_ok());
+    ///
+    /// backend.call("key");
+    /// assert_eq!(
+    ///     backend.verify().unwrap_err().to_string(),
+    ///     "mock did not meet 1 of 2 expectations:\n\
+    ///      - expected at most 1 call, but it was called 2 times"
+    /// );
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn expect_at_most(&self, n: usize) {
+        self.expect(Expectation::AtMost(n))
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    fn expect(&self, expectation: Expectation<A::Record>) {
+        self.expectations.write().unwrap().push(expectation)
+    }
+
+    /// Returns the number of times `Mock::call` has been called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, i64>::new(0);
+    ///
+    /// assert_eq!(mock.num_calls(), 0);
+    /// mock.call(5);
+    /// assert_eq!(mock.num_calls(), 1);
+    /// mock.call(10);
+    /// assert_eq!(mock.num_calls(), 2);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn num_calls(&self) -> usize {
+        self.num_calls.load(Ordering::SeqCst)
+    }
+
+    /// Returns the arguments to `Mock::call` in order from first to last, as
+    /// stored by the `Mock`'s `ArgRecord` policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, &str>::new("");
+    ///
+    /// mock.call("first");
+    /// mock.call("second");
+    /// mock.call("third");
+    ///
+    /// assert_eq!(mock.calls().as_slice(), ["first", "second", "third"]);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls(&self) -> Vec<A::Record> {
+        self.calls.read().unwrap().clone()
+    }
+
+    /// Returns the arguments of the most recently recorded call, or `None`
+    /// if no calls have been recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// assert_eq!(mock.last_call(), None);
+    ///
+    /// mock.call("first");
+    /// mock.call("second");
+    ///
+    /// assert_eq!(mock.last_call(), Some("second"));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn last_call(&self) -> Option<A::Record> {
+        self.calls.read().unwrap().last().cloned()
+    }
+
+    /// Returns the arguments of the recorded call at the zero-based index
+    /// `n`, or `None` if fewer calls have been recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    ///
+    /// mock.call("first");
+    /// mock.call("second");
+    ///
+    /// assert_eq!(mock.nth_call(0), Some("first"));
+    /// assert_eq!(mock.nth_call(1), Some("second"));
+    /// assert_eq!(mock.nth_call(2), None);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn nth_call(&self, n: usize) -> Option<A::Record> {
+        self.calls.read().unwrap().get(n).cloned()
+    }
+
+    /// Applies `f` to the recorded calls, in order from first to last,
+    /// without cloning them.
+    ///
+    /// The call history is locked while `f` runs, so `f` must not call the
+    /// `Mock`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let upload = Mock::<Vec<u8>, ()>::default();
+    /// upload.call(vec![0; 1024]);
+    /// upload.call(vec![0; 2048]);
+    ///
+    /// let total = upload.with_calls(|calls| calls.iter().map(Vec::len).sum::<usize>());
+    ///
+    /// assert_eq!(total, 3072);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn with_calls<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(&[A::Record]) -> T,
+    {
+        f(&self.calls.read().unwrap())
+    }
+
+    /// Reset the call history for the `Mock`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, &str>::default();
+    ///
+    /// mock.call("first");
+    /// mock.call("second");
+    ///
+    /// assert!(mock.called());
+    /// assert_eq!(mock.num_calls(), 2);
+    /// assert!(mock.called_with("first"));
+    /// assert!(mock.called_with("second"));
+    ///
+    /// mock.reset_calls();
+    ///
+    /// assert!(!mock.called());
+    /// assert_eq!(mock.num_calls(), 0);
+    /// assert!(!mock.called_with("first"));
+    /// assert!(!mock.called_with("second"));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn reset_calls(&self) {
+        self.calls.write().unwrap().clear();
+        self.num_calls.store(0, Ordering::SeqCst);
+
+        if let Some(ref mut call_index) = *self.call_index.write().unwrap() {
+            call_index.clear()
+        }
+
+        if let Some(ref mut timestamps) = *self.timestamps.write().unwrap() {
+            timestamps.clear()
+        }
+
+        self.sequences.write().unwrap().clear();
+        self.threads.write().unwrap().clear();
+        self.sources.write().unwrap().clear();
+
+        if let Some(ref mut returned) = *self.returned.write().unwrap() {
+            returned.clear()
+        }
+    }
+
+    /// Returns the recorded calls in order from first to last and removes
+    /// them from the call history, as one atomic operation.
+    ///
+    /// Unlike `Mock::reset_calls`, `Mock::num_calls` still counts the
+    /// drained calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    ///
+    /// mock.call("connect");
+    /// mock.call("login");
+    /// assert_eq!(mock.drain_calls(), vec!["connect", "login"]);
+    ///
+    /// mock.call("logout");
+    /// assert_eq!(mock.drain_calls(), vec!["logout"]);
+    /// assert_eq!(mock.num_calls(), 3);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn drain_calls(&self) -> Vec<A::Record> {
+        let mut call_index = self.call_index.write().unwrap();
+        let mut calls = self.calls.write().unwrap();
+
+        if let Some(ref mut call_index) = *call_index {
+            call_index.clear()
+        }
+
+        if let Some(ref mut timestamps) = *self.timestamps.write().unwrap() {
+            timestamps.clear()
+        }
+
+        self.sequences.write().unwrap().clear();
+        self.threads.write().unwrap().clear();
+        self.sources.write().unwrap().clear();
+
+        if let Some(ref mut returned) = *self.returned.write().unwrap() {
+            returned.clear()
+        }
+
+        mem::take(&mut *calls)
+    }
+
+    /// Reset the `Mock`'s behavior to returning the value given at
+    /// construction time, keeping its call history.
+    ///
+    /// This clears any functions, closures, sequences, return values for
+    /// specific arguments or calls, delegate and delay that have been
+    /// configured. Its name, context, resolution, strict mode and observers
+    /// set via `Mock::on_call` are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, i64>::new(0);
+    /// mock.use_fn(|x| x * 2);
+    /// mock.return_value_for(3, 30);
+    ///
+    /// assert_eq!(mock.call(3), 30);
+    /// assert_eq!(mock.call(4), 8);
+    ///
+    /// mock.reset_behavior();
+    ///
+    /// assert_eq!(mock.call(3), 0);
+    /// # #[cfg(not(feature = "disabled"))]
+    /// assert_eq!(mock.num_calls(), 3);
+    /// ```
+    pub fn reset_behavior(&self) {
+        let initial_return_value = self.initial_return_value.read().unwrap().clone();
+
+        *self.return_value.write().unwrap() = initial_return_value;
+        *self.mock_fn.write().unwrap() = None;
+        *self.mock_closure.write().unwrap() = None;
+        *self.mock_indexed_closure.write().unwrap() = None;
+        *self.delegate.write().unwrap() = None;
+        self.delegating.store(false, Ordering::SeqCst);
+        *self.sequence.write().unwrap() = None;
+        *self.delay.write().unwrap() = None;
+        self.once.write().unwrap().clear();
+        self.nth.write().unwrap().clear();
+        self.overrides.store(0, Ordering::SeqCst);
+        self.stubs.write().unwrap().clear();
+        *self.configured.write().unwrap() = Arc::new(vec![])
+    }
+
+    /// Reset the `Mock`'s behavior, expectations and call history.
+    ///
+    /// See `Mock::reset_behavior` and `Mock::reset_calls` for more details.
+    /// Settings that are not part of either are kept, namely:
+    ///
+    /// * its name, context, resolution and strict mode
+    /// * observers set via `Mock::on_call` or its derivatives, such as
+    ///   `Mock::subscribe` and `Mock::in_sequence`, and the `CallLog` set via
+    ///   `Mock::log_calls_to`
+    /// * how calls are recorded, as set via `Mock::sample_calls`,
+    ///   `Mock::with_history_capacity`, `Mock::disable_call_recording`,
+    ///   `Mock::record_timestamps`, `Mock::record_returns`,
+    ///   `Mock::index_calls`, `Mock::normalize_args` and the
+    ///   `Mock::limit_debug_*` methods
+    /// * values stored by `Mock::call_ref`, so that references to them remain
+    ///   valid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, bool>::new(false);
+    ///
+    /// for &(input, expected) in &[("yes", true), ("no", false)] {
+    ///     mock.reset();
+    ///     mock.return_value_for(input, expected);
+    ///
+    ///     assert_eq!(mock.call(input), expected);
+    /// #     #[cfg(not(feature = "disabled"))]
+    ///     assert_eq!(mock.calls(), vec![input]);
+    /// }
+    /// ```
+    pub fn reset(&self) {
+        self.reset_behavior();
+
+        #[cfg(not(feature = "disabled"))]
+        {
+            self.expectations.write().unwrap().clear();
+            self.reset_calls()
+        }
+
+        #[cfg(feature = "disabled")]
+        self.num_calls.store(0, Ordering::SeqCst)
+    }
+
+    /// Returns a handle for observing the call history that does not keep the
+    /// `Mock` alive.
+    ///
+    /// See `WeakMock` for more details.
+    #[cfg(not(feature = "disabled"))]
+    pub fn downgrade(&self) -> WeakMock<C, A> {
+        WeakMock::new(Arc::downgrade(&self.calls), Arc::downgrade(&self.num_calls))
+    }
+
+    /// Only store the arguments of every `every`th call, starting with the
+    /// first, while still counting every call.
+    ///
+    /// This bounds the memory used by high-volume tests while keeping some
+    /// visibility into the arguments used. Methods that inspect arguments,
+    /// such as `Mock::calls` and `Mock::called_with`, only see the sampled
+    /// calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<u64, ()>::default();
+    /// mock.sample_calls(100);
+    ///
+    /// for i in 0..1000 {
+    ///     mock.call(i);
+    /// }
+    ///
+    /// assert_eq!(mock.num_calls(), 1000);
+    /// assert_eq!(mock.calls().len(), 10);
+    /// assert_eq!(mock.calls()[..3], [0, 100, 200]);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn sample_calls(&self, every: usize) {
+        assert!(every > 0, "cannot sample every 0th call");
+        *self.sample_every.write().unwrap() = every
+    }
+
+    /// Only retain the arguments of the last `capacity` recorded calls,
+    /// discarding older calls as new ones are recorded.
+    ///
+    /// `Mock::num_calls` still counts every call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<usize, ()>::default();
+    /// mock.with_history_capacity(3);
+    ///
+    /// for i in 0..1000 {
+    ///     mock.call(i);
+    /// }
+    ///
+    /// assert_eq!(mock.num_calls(), 1000);
+    /// assert_eq!(mock.calls(), vec![997, 998, 999]);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn with_history_capacity(&self, capacity: usize) {
+        assert!(capacity > 0, "cannot retain 0 calls");
+        *self.history_capacity.write().unwrap() = Some(capacity);
+
+        let mut call_index = self.call_index.write().unwrap();
+        let mut calls = self.calls.write().unwrap();
+        self.trim_history(&mut call_index, &mut calls)
+    }
+
+    /// Stop storing the arguments of subsequent calls, while still counting
+    /// them.
+    ///
+    /// This avoids cloning arguments and locking the call history on every
+    /// call, for `Mock`s on hot paths such as benchmarks. Calls are not
+    /// logged to a `CallLog` either.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<Vec<u8>, ()>::default();
+    /// mock.call(vec![1]);
+    /// mock.disable_call_recording();
+    ///
+    /// for _ in 0..100 {
+    ///     mock.call(vec![0; 1024]);
+    /// }
+    ///
+    /// assert_eq!(mock.num_calls(), 101);
+    /// assert_eq!(mock.calls(), vec![vec![1]]);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn disable_call_recording(&self) {
+        self.recording_disabled.store(true, Ordering::SeqCst)
+    }
+
+    /// Returns true if every argument used for `Mock::call` satisfies
+    /// `predicate`.
+    ///
+    /// This is also true if the `Mock` has not been called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    /// mock.call(2);
+    /// mock.call(4);
+    ///
+    /// assert!(mock.only_called_with_matching(|x| x % 2 == 0));
+    ///
+    /// mock.call(5);
+    ///
+    /// assert!(!mock.only_called_with_matching(|x| x % 2 == 0));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn only_called_with_matching<F>(&self, predicate: F) -> bool
+    where
+        F: Fn(&A::Record) -> bool,
+    {
+        self.calls.read().unwrap().iter().all(predicate)
+    }
+
+    /// Returns true if any argument used for `Mock::call` satisfies
+    /// `predicate`.
+    ///
+    /// Unlike `Mock::called_with`, this does not require the arguments to
+    /// implement `PartialEq`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// #[derive(Clone)]
+    /// struct Request {
+    ///     path: String,
+    ///     retries: u32,
+    /// }
+    ///
+    /// let send = Mock::<Request, ()>::default();
+    /// send.call(Request { path: "/users".to_string(), retries: 0 });
+    ///
+    /// assert!(send.called_with_matching(|request| request.path == "/users"));
+    /// assert!(!send.called_with_matching(|request| request.retries > 0));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn called_with_matching<F>(&self, predicate: F) -> bool
+    where
+        F: Fn(&A::Record) -> bool,
+    {
+        self.calls.read().unwrap().iter().any(predicate)
+    }
+
+    /// Returns the arguments used for `Mock::call` that satisfy `predicate`,
+    /// in order from first to last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let write = Mock::<(&str, usize), ()>::default();
+    /// write.call(("a.txt", 10));
+    /// write.call(("b.txt", 0));
+    /// write.call(("c.txt", 20));
+    ///
+    /// assert_eq!(
+    ///     write.calls_where(|&(_, len)| len > 0),
+    ///     vec![("a.txt", 10), ("c.txt", 20)]
+    /// );
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls_where<F>(&self, predicate: F) -> Vec<A::Record>
+    where
+        F: Fn(&A::Record) -> bool,
+    {
+        self.calls
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|record| predicate(record))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns true if any argument used for `Mock::call` satisfies
+    /// `matcher`.
+    ///
+    /// For tuples of arguments, a tuple of matchers such as `eq` and `any`
+    /// checks each position separately. See `ArgMatcher` for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Instant;
+    ///
+    /// use pseudo::{any, eq, Mock};
+    ///
+    /// let expire = Mock::<(u64, Instant), ()>::default();
+    /// expire.call((11, Instant::now()));
+    ///
+    /// assert!(expire.called_with_args((eq(11), any())));
+    /// assert!(!expire.called_with_args((eq(12), any())));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn called_with_args<M>(&self, matcher: M) -> bool
+    where
+        M: ArgMatcher<A::Record>,
+    {
+        self.called_with_matching(|record| matcher.matches(record))
+    }
+
+    /// Set the value that `Mock::call_ref` returns a reference to.
+    ///
+    /// This is the same as `Mock::return_value`, so `Mock::call` returns a
+    /// clone of the value too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<(), String>::default();
+    /// mock.return_ref("name".to_string());
+    ///
+    /// assert_eq!(mock.call_ref(()), "name");
+    /// ```
+    pub fn return_ref<T: Into<R>>(&self, return_value: T) {
+        self.return_value(return_value)
+    }
+
+    /// Use the `Mock` to return a reference, keeping track of the arguments
+    /// used.
+    ///
+    /// This is intended for getter-style methods that return references. The
+    /// value is determined like that of `Mock::call`, but is then kept for as
+    /// long as the `Mock` or any of its clones is alive, even across
+    /// `Mock::reset`, so that previously returned references remain valid.
+    /// Each return value set via `Mock::return_ref` or a derivative, such as
+    /// `Mock::return_shared`, is kept once, however often it is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value would be produced by another behavior, such as a
+    /// function set via `Mock::use_fn`, since every call would then need a new
+    /// value to be kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// trait Config {
+    ///     fn name(&self) -> &str;
+    /// }
+    ///
+    /// struct MockConfig {
+    ///     name: Mock<(), String>,
+    /// }
+    ///
+    /// impl Config for MockConfig {
+    ///     fn name(&self) -> &str {
+    ///         self.name.call_ref(())
+    ///     }
+    /// }
+    ///
+    /// let mock = MockConfig { name: Mock::new("first") };
+    /// let first = mock.name();
+    ///
+    /// mock.name.return_ref("second");
+    ///
+    /// assert_eq!(first, "first");
+    /// assert_eq!(mock.name(), "second");
+    /// # #[cfg(not(feature = "disabled"))]
+    /// assert_eq!(mock.name.num_calls(), 2);
+    /// ```
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<u64, String>::default();
+    /// mock.use_fn(|id| id.to_string());
+    ///
+    /// mock.call_ref(1);
+    /// ```
+    pub fn call_ref(&self, args: C) -> &R {
+        let (call, response) = self.answer(args);
+
+        let return_value = match response {
+            Response::Shared(return_value) => return_value,
+            Response::Owned(_) => panic!(
+                "call_ref can only return values set via return_ref or one of its derivatives"
+            ),
+        };
+
+        self.record_return(call, &return_value);
+
+        let return_ref = Arc::as_ptr(&return_value);
+
+        self.return_refs
+            .write()
+            .unwrap()
+            .entry(return_ref as usize)
+            .or_insert(return_value);
+
+        // SAFETY: the value lives on the heap behind an `Arc` that is never
+        // removed from `return_refs`, which no method clears or shrinks,
+        // including `Mock::reset`. Since it is never dropped, no other value
+        // can take its address. `return_refs` is shared between clones and
+        // kept alive by `self`, so the value outlives the borrow of `self`
+        // even if other clones are dropped or reset in the meantime.
+        unsafe { &*return_ref }
+    }
+
+    /// Apply `normalizer` to recorded arguments, and to arguments being
+    /// matched against them, so that only their canonical forms are compared.
+    ///
+    /// Calls recorded before the normalizer is set are normalized too. The
+    /// arguments passed to functions or closures set via `Mock::use_fn` or
+    /// `Mock::use_closure` are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<String, ()>::default();
+    /// mock.normalize_args(Box::new(|path| path.to_lowercase()));
+    ///
+    /// mock.call("/Tmp/Data".to_string());
+    ///
+    /// assert!(mock.called_with("/tmp/data"));
+    /// assert!(mock.called_with("/TMP/DATA"));
+    /// assert_eq!(mock.calls(), vec!["/tmp/data".to_string()]);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn normalize_args(&self, normalizer: Normalizer<A::Record>) {
+        let mut call_index = self.call_index.write().unwrap();
+        let mut calls = self.calls.write().unwrap();
+        let normalized = calls.drain(..).map(&normalizer).collect();
+        *calls = normalized;
+
+        if let Some(ref mut call_index) = *call_index {
+            call_index.clear();
+
+            for record in calls.iter() {
+                call_index.insert(record);
+            }
+        }
+
+        *self.normalizer.write().unwrap() = Some(normalizer)
+    }
+
+    /// Truncate the arguments of each call to at most `max_len` bytes in
+    /// `Debug` output and failure messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<String, ()>::default();
+    /// mock.limit_debug_arg_len(8);
+    ///
+    /// mock.call("a".repeat(10_000));
+    ///
+    /// let debug = format!("{:?}", mock);
+    ///
+    /// assert!(debug.contains(r#""aaaaaaa... (9994 more bytes)"#));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn limit_debug_arg_len(&self, max_len: usize) {
+        self.debug_limits.write().unwrap().max_arg_len = Some(max_len)
+    }
+
+    /// Show at most `max_calls` calls in `Debug` output and failure messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<u64, ()>::default();
+    /// mock.limit_debug_calls(2);
+    ///
+    /// for i in 0..100 {
+    ///     mock.call(i);
+    /// }
+    ///
+    /// assert!(format!("{:?}", mock).contains("[0, 1, ... (98 more calls)]"));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn limit_debug_calls(&self, max_calls: usize) {
+        self.debug_limits.write().unwrap().max_calls = Some(max_calls)
+    }
+
+    /// Returns the arguments to `Mock::call` in order from first to last,
+    /// each paired with a sequence number that orders it relative to calls
+    /// to every other `Mock`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let connect = Mock::<&str, ()>::default();
+    /// let send = Mock::<Vec<u8>, ()>::default();
+    ///
+    /// connect.call("localhost");
+    /// send.call(vec![1, 2]);
+    ///
+    /// assert!(connect.calls_with_sequence()[0].0 < send.calls_with_sequence()[0].0);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls_with_sequence(&self) -> Vec<(usize, A::Record)> {
+        let calls = self.calls.read().unwrap();
+        let sequences = self.sequences.read().unwrap();
+
+        sequences
+            .iter()
+            .cloned()
+            .zip(calls.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the arguments to `Mock::call` grouped by the ID of the thread
+    /// that made each call, in order from first to last within each thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let process = Mock::<u64, ()>::default();
+    ///
+    /// let worker = {
+    ///     let process = process.clone();
+    ///     thread::spawn(move || {
+    ///         process.call(1);
+    ///         process.call(2);
+    ///         thread::current().id()
+    ///     })
+    /// }
+    /// .join()
+    /// .unwrap();
+    ///
+    /// process.call(3);
+    ///
+    /// let calls = process.calls_by_thread();
+    ///
+    /// assert_eq!(calls[&worker], vec![1, 2]);
+    /// assert_eq!(calls[&thread::current().id()], vec![3]);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls_by_thread(&self) -> HashMap<ThreadId, Vec<A::Record>> {
+        let calls = self.calls.read().unwrap();
+        let threads = self.threads.read().unwrap();
+        let mut by_thread = HashMap::new();
+
+        for (&thread, record) in threads.iter().zip(calls.iter()) {
+            by_thread
+                .entry(thread)
+                .or_insert_with(Vec::new)
+                .push(record.clone());
+        }
+
+        by_thread
+    }
+
+    /// Returns the arguments to `Mock::call` made from the thread `id`, in
+    /// order from first to last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let fetch = Mock::<u64, ()>::default();
+    /// fetch.call(1);
+    ///
+    /// let worker = {
+    ///     let fetch = fetch.clone();
+    ///     thread::spawn(move || fetch.call(2))
+    /// };
+    /// let worker_id = worker.thread().id();
+    /// worker.join().unwrap();
+    ///
+    /// assert_eq!(fetch.calls_for_thread(worker_id), vec![2]);
+    /// assert_eq!(fetch.calls_for_thread(thread::current().id()), vec![1]);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls_for_thread(&self, id: ThreadId) -> Vec<A::Record> {
+        let calls = self.calls.read().unwrap();
+        let threads = self.threads.read().unwrap();
+
+        threads
+            .iter()
+            .zip(calls.iter())
+            .filter(|&(&thread, _)| thread == id)
+            .map(|(_, record)| record.clone())
+            .collect()
+    }
+
+    /// Returns the arguments to `Mock::call` grouped by the ID of the thread
+    /// that made each call, with threads in the order of their first call
+    /// and calls in order from first to last within each thread.
+    ///
+    /// Unlike `Mock::calls_by_thread`, the order of the groups is
+    /// deterministic for a given call history.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let process = Mock::<&str, ()>::default();
+    /// process.call("a1");
+    ///
+    /// let worker = {
+    ///     let process = process.clone();
+    ///     thread::spawn(move || {
+    ///         process.call("b1");
+    ///         process.call("b2");
+    ///     })
+    /// };
+    /// let worker_id = worker.thread().id();
+    /// worker.join().unwrap();
+    ///
+    /// process.call("a2");
+    ///
+    /// assert_eq!(
+    ///     process.calls_grouped_by_thread(),
+    ///     vec![
+    ///         (thread::current().id(), vec!["a1", "a2"]),
+    ///         (worker_id, vec!["b1", "b2"]),
+    ///     ]
+    /// );
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls_grouped_by_thread(&self) -> Vec<(ThreadId, Vec<A::Record>)> {
+        let calls = self.calls.read().unwrap();
+        let threads = self.threads.read().unwrap();
+        let mut groups: Vec<(ThreadId, Vec<A::Record>)> = vec![];
+
+        for (&thread, record) in threads.iter().zip(calls.iter()) {
+            match groups.iter().position(|&(id, _)| id == thread) {
+                Some(i) => groups[i].1.push(record.clone()),
+                None => groups.push((thread, vec![record.clone()])),
+            }
+        }
+
+        groups
+    }
+
+    /// Returns the arguments to `Mock::call` in order from first to last,
+    /// each paired with the behavior that determined its return value.
+    ///
+    /// This helps to debug tests that switch between several behaviors.
+    /// See `CallRecord` for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::{Mock, ReturnSource};
+    ///
+    /// let mock = Mock::<&str, &str>::new("default");
+    /// mock.return_value_for("known", "stubbed");
+    ///
+    /// mock.call("known");
+    /// mock.call("unknown");
+    ///
+    /// let records = mock.call_records();
+    ///
+    /// assert_eq!(records[0].args, "known");
+    /// assert_eq!(records[0].source, Some(ReturnSource::Stub));
+    /// assert_eq!(records[1].source, Some(ReturnSource::Value));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn call_records(&self) -> Vec<CallRecord<A::Record>> {
+        let calls = self.calls.read().unwrap();
+        let sequences = self.sequences.read().unwrap();
+        let sources = self.sources.read().unwrap();
+
+        sequences
+            .iter()
+            .zip(calls.iter())
+            .map(|(sequence, args)| CallRecord {
+                args: args.clone(),
+                source: sources.get(sequence).cloned(),
+            })
+            .collect()
+    }
+
+    /// Store a clone of the value returned from each subsequent call, for
+    /// use with `Mock::calls_and_returns`.
+    ///
+    /// Only calls whose arguments are stored have their return values
+    /// stored; see `Mock::sample_calls`.
+    #[cfg(not(feature = "disabled"))]
+    pub fn record_returns(&self) {
+        let mut returned = self.returned.write().unwrap();
+
+        if returned.is_none() {
+            *returned = Some(HashMap::new());
+        }
+    }
+
+    /// Returns the arguments of each call made since `Mock::record_returns`,
+    /// paired with the value it returned, in order from first to last.
+    ///
+    /// Calls that panicked or are still in progress are left out.
+    ///
+    /// # Panics
+    ///
+    /// Panics if return values are not being recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// fn normalize(path: &str) -> String {
+    ///     path.trim_end_matches('/').to_string()
+    /// }
+    ///
+    /// let spy = Mock::<&str, String>::default();
+    /// spy.delegate_to(Box::new(normalize));
+    /// spy.record_returns();
+    ///
+    /// spy.call("/users/");
+    ///
+    /// assert_eq!(
+    ///     spy.calls_and_returns(),
+    ///     vec![("/users/", "/users".to_string())]
+    /// );
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls_and_returns(&self) -> Vec<(A::Record, R)> {
+        let calls = self.calls.read().unwrap();
+        let sequences = self.sequences.read().unwrap();
+
+        match *self.returned.read().unwrap() {
+            Some(ref returned) => sequences
+                .iter()
+                .zip(calls.iter())
+                .filter_map(|(sequence, args)| {
+                    returned
+                        .get(sequence)
+                        .map(|return_value| (args.clone(), return_value.clone()))
+                })
+                .collect(),
+            None => panic!("return values are not being recorded; use Mock::record_returns"),
+        }
+    }
+
+    /// Returns the arguments to both this `Mock` and `other`, merged into a
+    /// single history in the order the calls were made.
+    ///
+    /// Calls are only included once if the `Mock`s share their history. To
+    /// merge the histories of `Mock`s with different argument types, map the
+    /// results of `Mock::calls_with_sequence` to a common type and sort them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let read = Mock::<u64, ()>::default();
+    /// let write = Mock::<u64, bool>::new(true);
+    ///
+    /// read.call(1);
+    /// write.call(2);
+    /// read.call(3);
+    ///
+    /// assert_eq!(read.merge_calls(&write), vec![1, 2, 3]);
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn merge_calls<D, S, B>(&self, other: &Mock<D, S, B>) -> Vec<A::Record>
+    where
+        S: Clone,
+        B: ArgRecord<D, Record = A::Record>,
+    {
+        let mut merged = other.calls_with_sequence();
+        merged.extend(self.calls_with_sequence());
+        merged.sort_by_key(|&(sequence, _)| sequence);
+        merged.dedup_by_key(|&mut (sequence, _)| sequence);
+
+        merged.into_iter().map(|(_, record)| record).collect()
+    }
+
+    /// Record the time of each subsequent call, for use with timing
+    /// assertions such as `Mock::assert_called_within`.
+    ///
+    /// Only calls whose arguments are stored are timestamped; see
+    /// `Mock::sample_calls`.
+    #[cfg(not(feature = "disabled"))]
+    pub fn record_timestamps(&self) {
+        let mut timestamps = self.timestamps.write().unwrap();
+
+        if timestamps.is_none() {
+            *timestamps = Some(vec![]);
+        }
+    }
+
+    /// Returns the arguments of each call made since
+    /// `Mock::record_timestamps`, paired with the time it was made, in order
+    /// from first to last.
+    ///
+    /// # Panics
+    ///
+    /// Panics if timestamps are not being recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let connect = Mock::<&str, bool>::new(false);
+    /// connect.record_timestamps();
+    ///
+    /// connect.call("db");
+    /// thread::sleep(Duration::from_millis(10));
+    /// connect.call("db");
+    ///
+    /// let calls = connect.calls_with_timestamps();
+    ///
+    /// assert_eq!(calls.len(), 2);
+    /// assert!(calls[1].1 - calls[0].1 >= Duration::from_millis(10));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn calls_with_timestamps(&self) -> Vec<(A::Record, Instant)> {
+        let calls = self.calls.read().unwrap();
+
+        match *self.timestamps.read().unwrap() {
+            Some(ref timestamps) => calls[calls.len() - timestamps.len()..]
+                .iter()
+                .cloned()
+                .zip(timestamps.iter().cloned())
+                .collect(),
+            None => panic!("timestamps are not being recorded; use Mock::record_timestamps"),
+        }
+    }
+
+    /// Returns true if `Mock::call` was called no earlier than `since` and
+    /// no later than `budget` after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if timestamps are not being recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<u64, ()>::default();
+    /// mock.record_timestamps();
+    ///
+    /// mock.call(1);
+    ///
+    /// let mark = Instant::now();
+    ///
+    /// assert!(!mock.called_within(Duration::from_secs(60), mark));
+    ///
+    /// mock.call(2);
+    ///
+    /// assert!(mock.called_within(Duration::from_secs(60), mark));
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn called_within(&self, budget: Duration, since: Instant) -> bool {
+        match *self.timestamps.read().unwrap() {
+            Some(ref timestamps) => timestamps
+                .iter()
+                .any(|&t| t >= since && t - since <= budget),
+            None => panic!("timestamps are not being recorded; use Mock::record_timestamps"),
+        }
+    }
+
+    /// Panics unless `Mock::call` was called no earlier than `since` and no
+    /// later than `budget` after it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let flush = Mock::<(), ()>::default();
+    /// flush.record_timestamps();
+    ///
+    /// let mark = Instant::now();
+    /// flush.call(());
+    ///
+    /// flush.assert_called_within(Duration::from_secs(5), mark);
+    /// ```
+    ///
+    /// ```should_panic
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let flush = Mock::<(), ()>::default();
+    /// flush.record_timestamps();
+    ///
+    /// flush.call(());
+    ///
+    /// flush.assert_called_within(Duration::from_secs(5), Instant::now());
+    /// ```
+    #[cfg(not(feature = "disabled"))]
+    pub fn assert_called_within(&self, budget: Duration, since: Instant) {
+        if !self.called_within(budget, since) {
+            let timestamps = self.timestamps.read().unwrap();
+            let offsets: Vec<String> = timestamps
+                .iter()
+                .flat_map(|timestamps| timestamps.iter())
+                .map(|&t| {
+                    if t >= since {
+                        format!("+{:?}", t - since)
+                    } else {
+                        format!("-{:?}", since - t)
+                    }
+                })
+                .collect();
+
+            panic!(
+                "expected a call within {:?} of the mark, but calls were at [{}] relative to it",
+                budget,
+                offsets.join(", ")
+            );
+        }
+    }
+
+    /// Identify the `Mock` by `name` in verification errors and failure
+    /// messages.
+    #[cfg(not(feature = "disabled"))]
+    pub fn set_name<T: Into<String>>(&self, name: T) {
+        *self.name.write().unwrap() = Some(name.into())
+    }
+
+    pub(crate) fn add_stub(&self, matches: Matcher<C>, return_value: R) {
+        self.stubs.write().unwrap().push((matches, return_value));
+        self.configure(Behavior::Conditional)
+    }
+
+    fn push_once(&self, once: OneShot<C, R>) {
+        self.once.write().unwrap().push_back(once);
+        self.overrides.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Takes the behavior set for `call` via `Mock::on_nth_call`, or else the
+    /// next one set for a single call, without locking either if none are
+    /// pending.
+    fn take_override(&self, call: CallId) -> Option<(OneShot<C, R>, ReturnSource)> {
+        if self.overrides.load(Ordering::SeqCst) == 0 {
+            return None;
+        }
+
+        let taken = match self.nth.write().unwrap().remove(&call.index) {
+            Some(return_value) => Some((OneShot::Value(return_value), ReturnSource::NthCall)),
+            None => self
+                .once
+                .write()
+                .unwrap()
+                .pop_front()
+                .map(|once| (once, ReturnSource::Once)),
+        };
+
+        if taken.is_some() {
+            self.overrides.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        taken
+    }
+
+    fn context(&self) -> Option<Context> {
+        self.context.read().unwrap().clone()
+    }
+
+    fn configure(&self, behavior: Behavior) {
+        let mut configured = self.configured.write().unwrap();
+
+        if configured.last() != Some(&behavior) {
+            Arc::make_mut(&mut configured).push(behavior);
+        }
+    }
+
+    fn respond(&self, args: C, call: CallId, configured: &[Behavior]) -> Response<R> {
+        match self.resolution.read().unwrap().resolve(configured) {
+            Behavior::Conditional => {
+                let stubbed = self
+                    .stubs
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .find(|(matches, _)| matches(&args))
+                    .map(|(_, return_value)| return_value.clone());
+
+                match stubbed {
+                    Some(return_value) => {
+                        self.served(call, ReturnSource::Stub);
+                        Response::Owned(return_value)
+                    }
+                    None => self.fall_back(args, call, configured, Behavior::Conditional),
+                }
+            }
+            Behavior::Sequence => {
+                let next = match *self.sequence.write().unwrap() {
+                    Some(ref mut sequence) => sequence.next(),
+                    None => Ok(None),
+                };
+
+                match next {
+                    Ok(Some(return_value)) => {
+                        self.served(call, ReturnSource::Sequence);
+                        Response::Owned(return_value)
+                    }
+                    Ok(None) => self.fall_back(args, call, configured, Behavior::Sequence),
+                    Err(message) => {
+                        self.served(call, ReturnSource::Sequence);
+                        panic!("{}", message)
+                    }
+                }
+            }
+            Behavior::Function => {
+                let context = self.context();
+
+                if let Some(ref mock_fn) = *self.mock_fn.read().unwrap() {
+                    self.served(call, ReturnSource::Fn);
+                    return Response::Owned(with_context(context, || mock_fn(args)));
+                }
+
+                if let Some(ref mock_closure) = *self.mock_closure.read().unwrap() {
+                    self.served(call, ReturnSource::Closure);
+                    return Response::Owned(with_context(context, || mock_closure(args)));
+                }
+
+                if let Some(ref mock_closure) = *self.mock_indexed_closure.read().unwrap() {
+                    self.served(call, ReturnSource::IndexedClosure);
+                    return Response::Owned(with_context(context, || {
+                        mock_closure(call.index, args)
+                    }));
+                }
+
+                self.fall_back(args, call, configured, Behavior::Function)
+            }
+            Behavior::Value => {
+                self.served(call, ReturnSource::Value);
+                Response::Shared(self.with_shared_return_value(Arc::clone))
+            }
+        }
+    }
+
+    /// Applies `f` to the current return value, initializing it first if it
+    /// was created with `Mock::new_lazy`.
+    fn with_return_value<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(&R) -> T,
+    {
+        self.with_shared_return_value(|return_value| f(return_value))
+    }
+
+    fn with_shared_return_value<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(&Arc<R>) -> T,
+    {
+        if let Some(ref return_value) = *self.return_value.read().unwrap() {
+            return f(return_value);
+        }
+
+        let mut return_value = self.return_value.write().unwrap();
+
+        if return_value.is_none() {
+            let init = self.lazy_init.lock().unwrap().take();
+            let init = init.expect("mock has a return value or a lazy initializer");
+            let initial = Arc::new(init());
+            *self.initial_return_value.write().unwrap() = Some(initial.clone());
+            *return_value = Some(initial);
+        }
+
+        f(return_value.as_ref().unwrap())
+    }
+
+    fn sleep(&self) {
+        let delay = *self.delay.read().unwrap();
+
+        if let Some((min, max)) = delay {
+            let random = self.rng.lock().unwrap().next();
+
+            thread::sleep(jitter(min, max, random))
+        }
+    }
+
+    fn fall_back(
+        &self,
+        args: C,
+        call: CallId,
+        configured: &[Behavior],
+        exhausted: Behavior,
+    ) -> Response<R> {
+        let remaining = configured
+            .iter()
+            .cloned()
+            .filter(|&behavior| behavior != exhausted)
+            .collect::<Vec<_>>();
+
+        self.respond(args, call, &remaining)
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    fn normalize(&self, record: A::Record) -> A::Record {
+        match *self.normalizer.read().unwrap() {
+            Some(ref normalizer) => normalizer(record),
+            None => record,
+        }
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    fn normalize_all(&self, records: &[A::Record]) -> Vec<A::Record> {
+        records
+            .iter()
+            .map(|record| self.normalize(record.clone()))
+            .collect()
+    }
+
+    // `usize::is_multiple_of` needs a much newer compiler than the crate
+    // otherwise supports.
+    #[cfg(not(feature = "disabled"))]
+    #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+    fn record_call(&self, args: &C) -> CallId {
+        let mut call = CallId {
+            index: self.num_calls.fetch_add(1, Ordering::SeqCst),
+            sequence: None,
+        };
+
+        if self.recording_disabled.load(Ordering::SeqCst) {
+            return call;
+        }
+
+        if let Some(ref log_call) = *self.call_log.read().unwrap() {
+            log_call(args);
+        }
+
+        let sample_every = *self.sample_every.read().unwrap();
+
+        if !self.arg_record.keeps_history() || call.index % sample_every != 0 {
+            return call;
+        }
+
+        let record = self.normalize(self.arg_record.record(args));
+
+        let mut call_index = self.call_index.write().unwrap();
+
+        if let Some(ref mut call_index) = *call_index {
+            call_index.insert(&record);
+        }
+
+        let mut calls = self.calls.write().unwrap();
+        calls.push(record);
+        let sequence = NEXT_SEQUENCE.fetch_add(1, Ordering::SeqCst);
+        self.sequences.write().unwrap().push(sequence);
+        call.sequence = Some(sequence);
+        self.threads.write().unwrap().push(thread::current().id());
+
+        if let Some(ref mut timestamps) = *self.timestamps.write().unwrap() {
+            timestamps.push(Instant::now());
+        }
+
+        self.trim_history(&mut call_index, &mut calls);
+
+        call
+    }
+
+    /// Only counts the call, since nothing else is tracked.
+    #[cfg(feature = "disabled")]
+    #[inline]
+    fn record_call(&self, _: &C) -> CallId {
+        CallId {
+            index: self.num_calls.fetch_add(1, Ordering::SeqCst),
+        }
+    }
+
+    /// Wakes up any threads waiting for calls and runs the observers set via
+    /// `Mock::on_call`.
+    #[cfg(not(feature = "disabled"))]
+    fn notify_call(&self, args: &C) {
+        if self.waiters.load(Ordering::SeqCst) > 0 {
+            self.signal_call();
+        }
+
+        if self.observed.load(Ordering::SeqCst) {
+            for observer in self.observers.read().unwrap().iter() {
+                observer(args);
+            }
+        }
+    }
+
+    #[cfg(feature = "disabled")]
+    #[inline]
+    fn notify_call(&self, _: &C) {}
+
+    /// Wakes up any threads waiting in `Mock::wait_called` or one of its
+    /// derivatives.
+    #[cfg(not(feature = "disabled"))]
+    fn signal_call(&self) {
+        let (ref lock, ref signal) = *self.call_signal;
+        let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+        signal.notify_all()
+    }
+
+    /// Blocks until `condition` holds, checking it again after every call,
+    /// or until `timeout` has elapsed. Returns whether `condition` held.
+    #[cfg(not(feature = "disabled"))]
+    fn wait_until<F>(&self, timeout: Duration, condition: F) -> bool
+    where
+        F: Fn() -> bool,
+    {
+        // Registering before checking `condition` means that a call either
+        // happens before the check or sees the waiter and signals it.
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+
+        let deadline = Instant::now() + timeout;
+        let (ref lock, ref signal) = *self.call_signal;
+        let mut guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+
+        let held = loop {
+            if condition() {
+                break true;
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                break false;
+            }
+
+            guard = signal
+                .wait_timeout(guard, deadline - now)
+                .unwrap_or_else(|err| err.into_inner())
+                .0;
+        };
+
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+
+        held
+    }
+
+    /// Stores a clone of the value returned from `call`, if returned values
+    /// are being recorded.
+    #[cfg(not(feature = "disabled"))]
+    fn record_return(&self, call: CallId, return_value: &R) {
+        if let Some(sequence) = call.sequence {
+            if let Some(ref mut returned) = *self.returned.write().unwrap() {
+                returned.insert(sequence, return_value.clone());
+            }
+        }
+    }
+
+    #[cfg(feature = "disabled")]
+    #[inline]
+    fn record_return(&self, _: CallId, _: &R) {}
+
+    /// Records that `source` determined the return value of `call`.
+    #[cfg(not(feature = "disabled"))]
+    fn served(&self, call: CallId, source: ReturnSource) {
+        if let Some(sequence) = call.sequence {
+            self.sources.write().unwrap().insert(sequence, source);
+        }
+    }
+
+    #[cfg(feature = "disabled")]
+    #[inline]
+    fn served(&self, _: CallId, _: ReturnSource) {}
+
+    /// Discards the oldest calls beyond the capacity set via
+    /// `Mock::with_history_capacity`, along with their metadata.
+    #[cfg(not(feature = "disabled"))]
+    fn trim_history(
+        &self,
+        call_index: &mut Option<Box<dyn CallIndex<A::Record> + Send + Sync>>,
+        calls: &mut Vec<A::Record>,
+    ) {
+        let excess = match *self.history_capacity.read().unwrap() {
+            Some(capacity) if calls.len() > capacity => calls.len() - capacity,
+            _ => return,
+        };
+
+        for record in calls.drain(..excess) {
+            if let Some(ref mut call_index) = *call_index {
+                call_index.remove(&record);
+            }
+        }
+
+        let mut sequences = self.sequences.write().unwrap();
+        let mut sources = self.sources.write().unwrap();
+        let mut returned = self.returned.write().unwrap();
+
+        for sequence in sequences.drain(..excess) {
+            sources.remove(&sequence);
+
+            if let Some(ref mut returned) = *returned {
+                returned.remove(&sequence);
+            }
+        }
+
+        self.threads.write().unwrap().drain(..excess);
+
+        if let Some(ref mut timestamps) = *self.timestamps.write().unwrap() {
+            let excess = timestamps.len().saturating_sub(calls.len());
+            timestamps.drain(..excess);
+        }
+    }
+}
+
+impl<C, R, A> Mock<C, R, A>
+where
+    C: PartialEq + Send + Sync + 'static,
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    /// Return `return_value` whenever `Mock::call` is called with `args`,
+    /// falling back to the `Mock`'s other behavior for other arguments.
+    ///
+    /// If several return values are set for the same arguments, the first
+    /// one wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let lookup = Mock::<(&str, u64), Option<&str>>::default();
+    /// lookup.return_value_for(("users", 1), Some("alice"));
+    /// lookup.return_value_for(("users", 2), Some("bob"));
+    ///
+    /// assert_eq!(lookup.call(("users", 1)), Some("alice"));
+    /// assert_eq!(lookup.call(("users", 2)), Some("bob"));
+    /// assert_eq!(lookup.call(("users", 3)), None);
+    /// ```
+    pub fn return_value_for<T: Into<R>>(&self, args: C, return_value: T) {
+        self.add_stub(Box::new(move |call| *call == args), return_value.into())
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<C, R, A> Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C> + Clone,
+{
+    /// Creates a new `Mock` that will return `return_value` and shares its
+    /// call history with `other`.
+    ///
+    /// Calls to either `Mock` are recorded in the same, ordered history, so
+    /// that several methods can be verified as a single logical operation.
+    /// Both `Mock`s must take the same arguments; to combine the histories of
+    /// `Mock`s with different argument types, use a `CallLog`.
+    ///
+    /// The history's settings, such as `Mock::normalize_args` and
+    /// `Mock::sample_calls`, are shared as well. Return values recorded via
+    /// `Mock::record_returns` and expectations checked by `Mock::verify` are
+    /// not, since the `Mock`s may return different types: each `Mock` only
+    /// sees the values it returned itself and checks only its own
+    /// expectations, albeit against the shared history.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let get_by_id = Mock::<String, Option<u64>>::default();
+    /// let exists = Mock::<String, bool>::sharing_history_with(&get_by_id, false);
+    ///
+    /// get_by_id.call("42".to_string());
+    /// exists.call("7".to_string());
+    ///
+    /// assert_eq!(get_by_id.num_calls(), 2);
+    /// assert_eq!(exists.calls(), vec!["42".to_string(), "7".to_string()]);
+    /// ```
+    pub fn sharing_history_with<S, T>(other: &Mock<C, S, A>, return_value: T) -> Self
+    where
+        S: Clone,
+        T: Into<R>,
+    {
+        Mock {
+            calls: other.calls.clone(),
+            num_calls: other.num_calls.clone(),
+            call_signal: other.call_signal.clone(),
+            waiters: other.waiters.clone(),
+            recording_disabled: other.recording_disabled.clone(),
+            sample_every: other.sample_every.clone(),
+            history_capacity: other.history_capacity.clone(),
+            call_index: other.call_index.clone(),
+            timestamps: other.timestamps.clone(),
+            sequences: other.sequences.clone(),
+            threads: other.threads.clone(),
+            sources: other.sources.clone(),
+            normalizer: other.normalizer.clone(),
+            ..Self::with_arg_record(return_value, other.arg_record.clone())
+        }
+    }
+}
+
+impl<C, R, A> Mock<C, R, A>
+where
+    C: 'static,
+    R: Clone + 'static,
+    A: ArgRecord<C>,
+{
+    /// Return values received from `receiver`, waiting up to `timeout` on
+    /// each call for one to be sent.
+    ///
+    /// This lets a test control when a `Mock` that is called from another
+    /// thread responds.
+    ///
+    /// # Panics
+    ///
+    /// `Mock::call` panics if no value is received within `timeout`, or if
+    /// the sending half of the channel has been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::mpsc::channel;
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let fetch = Mock::<&str, String>::default();
+    /// let (tx, rx) = channel();
+    /// fetch.use_receiver(rx, Duration::from_secs(5));
+    ///
+    /// let worker = {
+    ///     let fetch = fetch.clone();
+    ///     thread::spawn(move || fetch.call("/status"))
+    /// };
+    ///
+    /// tx.send("ok".to_string()).unwrap();
+    ///
+    /// assert_eq!(worker.join().unwrap(), "ok");
+    /// ```
+    pub fn use_receiver(&self, receiver: Receiver<R>, timeout: Duration)
+    where
+        R: Send,
+    {
+        let receiver = Mutex::new(receiver);
+
+        self.use_closure(Box::new(move |_| {
+            let received = receiver
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .recv_timeout(timeout);
+
+            match received {
+                Ok(return_value) => return_value,
+                Err(RecvTimeoutError::Timeout) => {
+                    panic!(
+                        "mock timed out after {:?} waiting for a return value",
+                        timeout
+                    )
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    panic!("mock's return value channel was disconnected")
+                }
+            }
+        }))
+    }
+
+    /// Return the values produced by `iter` in order on successive calls,
+    /// then keep returning the last one.
+    ///
+    /// Values are only drawn from `iter` as they are needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let next_id = Mock::<(), u64>::default();
+    /// next_id.use_iterator((1..).step_by(10));
+    ///
+    /// assert_eq!(next_id.call(()), 1);
+    /// assert_eq!(next_id.call(()), 11);
+    /// assert_eq!(next_id.call(()), 21);
+    /// ```
+    pub fn use_iterator<I>(&self, iter: I)
+    where
+        I: IntoIterator<Item = R>,
+        I::IntoIter: Send + 'static,
+    {
+        self.use_iterator_then(iter, Exhaustion::RepeatLast)
+    }
+
+    /// Return the values produced by `iter` in order on successive calls,
+    /// then behave according to `exhaustion`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::{Exhaustion, Mock};
+    ///
+    /// let read_line = Mock::<(), Option<String>>::default();
+    /// let lines = "a\nb".lines().map(|line| Some(line.to_string())).collect::<Vec<_>>();
+    /// read_line.use_iterator_then(lines, Exhaustion::Fallback);
+    ///
+    /// assert_eq!(read_line.call(()), Some("a".to_string()));
+    /// assert_eq!(read_line.call(()), Some("b".to_string()));
+    /// assert_eq!(read_line.call(()), None);
+    /// ```
+    pub fn use_iterator_then<I>(&self, iter: I, exhaustion: Exhaustion)
+    where
+        I: IntoIterator<Item = R>,
+        I::IntoIter: Send + 'static,
+    {
+        let sequence = ReturnSequence::from_iter(Box::new(iter.into_iter()), exhaustion);
+        *self.sequence.write().unwrap() = Some(sequence);
+        self.configure(Behavior::Sequence)
+    }
+
+    /// Specify a closure that may mutate its captured state to determine the
+    /// `Mock`'s return value based on the arguments provided to `Mock::call`.
+    ///
+    /// Calls to the closure are serialized by a lock, so a closure that calls
+    /// the same `Mock` will deadlock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<u64, u64>::default();
+    /// let mut total = 0;
+    /// mock.use_closure_mut(Box::new(move |x| {
+    ///     total += x;
+    ///     total
+    /// }));
+    ///
+    /// assert_eq!(mock.call(1), 1);
+    /// assert_eq!(mock.call(2), 3);
+    /// assert_eq!(mock.call(3), 6);
+    /// ```
+    pub fn use_closure_mut(&self, mock_fn: Box<dyn FnMut(C) -> R + Send>) {
+        let mock_fn = Mutex::new(mock_fn);
+
+        self.use_closure(Box::new(move |args| {
+            let mut mock_fn = mock_fn.lock().unwrap_or_else(|err| err.into_inner());
+            mock_fn(args)
+        }))
+    }
+}
+
+impl<C, R> Mock<C, R>
+where
+    C: Clone + Debug,
+    R: Clone + Default,
+{
+    /// Creates a new `Mock` that panics whenever it is called.
+    ///
+    /// This is useful for asserting that a dependency is not used by a code
+    /// path. See `Mock::panic_with` for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    ///
+    /// let delete = Mock::<&str, ()>::never();
+    ///
+    /// // Panics with: mock must not be called, but was called with "users"
+    /// delete.call("users");
+    /// ```
+    pub fn never() -> Self {
+        let mock = Self::default();
+        mock.panic_with("mock must not be called");
+        mock
+    }
+}
+
+impl<C, R, A> Mock<C, R, A>
+where
+    C: Debug,
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    /// Panic with `message` and the arguments used whenever `Mock::call` is
+    /// called.
+    ///
+    /// This replaces any function or closure set via `Mock::use_fn` or
+    /// `Mock::use_closure`. The call is still recorded before panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    ///
+    /// let charge = Mock::<u64, bool>::new(true);
+    /// charge.panic_with("card must not be charged for free orders");
+    ///
+    /// // Panics with: card must not be charged for free orders, but was
+    /// // called with 0
+    /// charge.call(0);
+    /// ```
+    pub fn panic_with(&self, message: &str) {
+        let message = message.to_string();
+
+        self.use_closure(Box::new(move |args| {
+            panic!("{}, but was called with {:?}", message, args)
+        }))
+    }
+
+    /// Panic on `Mock::call` if its arguments do not match any of those
+    /// registered via `Mock::return_value_for` or `Mock::when`.
+    ///
+    /// The call is still recorded before panicking, and the panic message
+    /// shows the unexpected arguments. Values set via `Mock::on_nth_call`,
+    /// `Mock::return_value_once` and `Mock::delegate_to` are still returned
+    /// for any arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    ///
+    /// let lookup = Mock::<&str, i64>::default();
+    /// lookup.strict();
+    /// lookup.return_value_for("alice", 1);
+    ///
+    /// assert_eq!(lookup.call("alice"), 1);
+    ///
+    /// // Panics with: mock was called with unexpected arguments "bob"
+    /// lookup.call("bob");
+    /// ```
+    pub fn strict(&self) {
+        *self.strict.write().unwrap() = Some(|args| format!("{:?}", args))
+    }
+}
+
+impl<C, R, A> Mock<C, R, A>
+where
+    C: 'static,
+    R: Clone + Send + Sync + 'static,
+    A: ArgRecord<C> + Clone + Send + Sync + 'static,
+    A::Record: Send + Sync,
+{
+    /// Returns a callback that forwards to `Mock::call`.
+    ///
+    /// This is useful when a mocked method returns a callback, such as an
+    /// unsubscribe function: return the callback of a second `Mock` and assert
+    /// on that `Mock` to check whether and how the callback was invoked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// type Unsubscribe = Arc<dyn Fn(()) + Send + Sync>;
+    ///
+    /// let unsubscribe = Mock::<(), ()>::default();
+    /// let subscribe = Mock::<&str, Unsubscribe>::new(unsubscribe.callback());
+    ///
+    /// let callback = subscribe.call("topic");
+    ///
+    /// # #[cfg(not(feature = "disabled"))]
+    /// assert!(!unsubscribe.called());
+    /// callback(());
+    /// # #[cfg(not(feature = "disabled"))]
+    /// assert!(unsubscribe.called());
+    /// ```
+    pub fn callback(&self) -> Arc<dyn Fn(C) -> R + Send + Sync> {
+        let mock = self.clone();
+        Arc::new(move |args| mock.call(args))
+    }
+
+    /// Returns a boxed callback that forwards to `Mock::call`.
+    ///
+    /// See `Mock::callback` for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::{FnMock, Mock};
+    ///
+    /// let on_done = Mock::<u64, ()>::default();
+    /// let handler = on_done.clone();
+    /// let register = FnMock::<(), Box<dyn Fn(u64) + Send + Sync>>::from_closure(Box::new(
+    ///     move |_| handler.boxed_callback(),
+    /// ));
+    ///
+    /// register.call(())(42);
+    ///
+    /// # #[cfg(not(feature = "disabled"))]
+    /// assert!(on_done.called_with(42u64));
+    /// ```
+    pub fn boxed_callback(&self) -> Box<dyn Fn(C) -> R + Send + Sync> {
+        let mock = self.clone();
+        Box::new(move |args| mock.call(args))
+    }
+}
+
+impl<C, R> Default for Mock<C, R>
+where
+    C: Clone,
+    R: Clone + Default,
+{
+    /// Use `R::default()` as the initial return value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, i64>::default();
+    /// assert_eq!(mock.call(10), 0);
+    ///
+    /// let mock = Mock::<(), String>::default();
+    /// assert_eq!(&mock.call(()), "");
+    ///
+    /// let mock = Mock::<(i64, &str), Option<bool>>::default();
+    /// assert_eq!(mock.call((10, "test")), None);
+    /// ```
+    fn default() -> Self {
+        Self::new(R::default())
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<C, R, A> Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: PartialEq,
+{
+    /// Returns true if the specified argument has been used for `Mock::call`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::new(());
+    /// mock.call("foo");
+    /// mock.call("bar");
+    ///
+    /// assert!(mock.called_with("foo"));
+    /// assert!(mock.called_with("bar"));
+    /// assert!(!mock.called_with("baz"));
+    /// ```
+    pub fn called_with<T: Into<A::Record>>(&self, args: T) -> bool {
+        let args = self.normalize(args.into());
+
+        if let Some(ref call_index) = *self.call_index.read().unwrap() {
+            return call_index.count(&args) > 0;
+        }
+
+        self.calls.read().unwrap().contains(&args)
+    }
+
+    /// Returns true if an argument equal to `args` has been used for
+    /// `Mock::call`, comparing borrowed forms so that no owned value has to
+    /// be allocated.
+    ///
+    /// Unlike `Mock::called_with`, `args` is not normalized with the
+    /// function set via `Mock::normalize_args`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<String, ()>::default();
+    /// mock.call("foo".to_string());
+    ///
+    /// assert!(mock.called_with_ref("foo"));
+    /// assert!(!mock.called_with_ref("bar"));
+    /// ```
+    pub fn called_with_ref<Q>(&self, args: &Q) -> bool
+    where
+        A::Record: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.calls
+            .read()
+            .unwrap()
+            .iter()
+            .any(|record| record.borrow() == args)
+    }
+
+    /// Expect the specified argument to be used for `Mock::call`, as checked
+    /// by `Mock::verify`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.expect_called_with("foo");
+    ///
+    /// mock.call("bar");
+    /// assert!(mock.verify().is_err());
+    ///
+    /// mock.call("foo");
+    /// assert!(mock.verify().is_ok());
+    /// ```
+    pub fn expect_called_with<T: Into<A::Record>>(&self, args: T) {
+        let args = self.normalize(args.into());
+        self.expect(Expectation::CalledWith(args))
+    }
+
+    /// Returns the number of times the specified argument has been used for
+    /// `Mock::call`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::new(());
+    /// mock.call("foo");
+    /// mock.call("bar");
+    /// mock.call("foo");
+    ///
+    /// assert_eq!(mock.call_count_with("foo"), 2);
+    /// assert_eq!(mock.call_count_with("bar"), 1);
+    /// assert_eq!(mock.call_count_with("baz"), 0);
+    /// ```
+    pub fn call_count_with<T: Into<A::Record>>(&self, args: T) -> usize {
+        let args = self.normalize(args.into());
+
+        if let Some(ref call_index) = *self.call_index.read().unwrap() {
+            return call_index.count(&args);
+        }
+
+        self.calls
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|record| **record == args)
+            .count()
+    }
+
+    /// Returns the number of times the specified argument has been used for
+    /// `Mock::call`, for asserting on an exact count.
+    ///
+    /// This is an alias for `Mock::call_count_with`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let retry = Mock::<&str, bool>::new(false);
+    /// retry.call("GET /");
+    /// retry.call("GET /");
+    ///
+    /// assert_eq!(retry.times_called_with("GET /"), 2);
+    /// ```
+    pub fn times_called_with<T: Into<A::Record>>(&self, args: T) -> usize {
+        self.call_count_with(args)
+    }
+
+    /// Returns true if `Mock::call` has been called exactly once, with the
+    /// specified argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("foo");
+    ///
+    /// assert!(mock.called_once_with("foo"));
+    /// assert!(!mock.called_once_with("bar"));
+    ///
+    /// mock.call("foo");
+    ///
+    /// assert!(!mock.called_once_with("foo"));
+    /// ```
+    pub fn called_once_with<T: Into<A::Record>>(&self, args: T) -> bool {
+        self.called_once() && self.called_with(args)
+    }
+
+    /// Blocks until the specified argument has been used for `Mock::call`,
+    /// or until `timeout` has elapsed. Returns true if it was used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let ack = Mock::<i64, ()>::default();
+    ///
+    /// {
+    ///     let ack = ack.clone();
+    ///     thread::spawn(move || {
+    ///         for id in 0..5 {
+    ///             ack.call(id);
+    ///         }
+    ///     });
+    /// }
+    ///
+    /// assert!(ack.wait_called_with(4, Duration::from_secs(10)));
+    /// assert!(!ack.wait_called_with(5, Duration::from_millis(10)));
+    /// ```
+    pub fn wait_called_with<T: Into<A::Record>>(&self, args: T, timeout: Duration) -> bool {
+        let args = args.into();
+        self.wait_until(timeout, || self.called_with(args.clone()))
+    }
+
+    /// Returns true if the arguments to `Mock::call` are exactly `expected`,
+    /// in the same order and with no other calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("first");
+    /// mock.call("second");
+    ///
+    /// assert!(mock.called_with_exactly(&["first", "second"]));
+    /// assert!(!mock.called_with_exactly(&["second", "first"]));
+    /// assert!(!mock.called_with_exactly(&["first"]));
+    /// ```
+    pub fn called_with_exactly(&self, expected: &[A::Record]) -> bool {
+        *self.calls.read().unwrap() == self.normalize_all(expected)
+    }
+
+    /// Returns true if every argument in `expected` has been used for
+    /// `Mock::call`, in any order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("b");
+    /// mock.call("c");
+    /// mock.call("a");
+    ///
+    /// assert!(mock.called_with_all(&["a", "b"]));
+    /// assert!(!mock.called_with_all(&["a", "d"]));
+    /// ```
+    pub fn called_with_all(&self, expected: &[A::Record]) -> bool {
+        let expected = self.normalize_all(expected);
+        let calls = self.calls.read().unwrap();
+        expected.iter().all(|args| calls.contains(args))
+    }
+
+    /// Returns true if every call to `Mock::call` used the specified
+    /// argument, i.e. it was never called with anything else.
+    ///
+    /// This is also true if the `Mock` has not been called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("tenant-a");
+    /// mock.call("tenant-a");
+    ///
+    /// assert!(mock.only_called_with("tenant-a"));
+    ///
+    /// mock.call("tenant-b");
+    ///
+    /// assert!(!mock.only_called_with("tenant-a"));
+    /// ```
+    pub fn only_called_with<T: Into<A::Record>>(&self, args: T) -> bool {
+        let args = self.normalize(args.into());
+        self.only_called_with_matching(|record| *record == args)
+    }
+
+    /// Returns true if `other` has the same call history as this `Mock`.
+    ///
+    /// The `Mock`s may have different return types, as long as their
+    /// arguments are stored the same way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let live = Mock::<&str, ()>::default();
+    /// let replay = Mock::<&str, bool>::new(true);
+    ///
+    /// for step in &["open", "read"] {
+    ///     live.call(step);
+    ///     replay.call(step);
+    /// }
+    ///
+    /// assert!(live.calls_eq(&replay));
+    ///
+    /// live.call("close");
+    ///
+    /// assert!(!live.calls_eq(&replay));
+    /// ```
+    pub fn calls_eq<D, S, B>(&self, other: &Mock<D, S, B>) -> bool
+    where
+        S: Clone,
+        B: ArgRecord<D, Record = A::Record>,
+    {
+        *self.calls.read().unwrap() == other.calls()
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<C, R, A> Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: PartialEq + Debug,
+{
+    /// Panics unless the arguments to `Mock::call` are exactly `expected`, in
+    /// the same order and with no other calls.
+    ///
+    /// The panic message includes a diff of the expected and actual calls.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("first");
+    /// mock.call("third");
+    ///
+    /// // Panics with:
+    /// //
+    /// //   0: "first"
+    /// // - 1: "second"
+    /// // + 1: "third"
+    /// mock.assert_called_with_exactly(&["first", "second"]);
+    /// ```
+    pub fn assert_called_with_exactly(&self, expected: &[A::Record]) {
+        if let Err(err) = self.verify_called_with_exactly(expected) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Returns an error unless the arguments to `Mock::call` are exactly
+    /// `expected`, in the same order and with no other calls.
+    ///
+    /// This is the fallible equivalent of `Mock::assert_called_with_exactly`.
+    pub fn verify_called_with_exactly(
+        &self,
+        expected: &[A::Record],
+    ) -> Result<(), VerificationError> {
+        let expected = self.normalize_all(expected);
+        let calls = self.calls.read().unwrap();
+
+        if *calls == expected {
+            return Ok(());
+        }
+
+        let debug_limits = *self.debug_limits.read().unwrap();
+
+        Err(self.verification_error(
+            debug_limits.format_calls(&expected),
+            &calls,
+            format!(
+                "mock was not called with exactly the expected arguments:\n{}",
+                diff_calls(&expected, &calls, &debug_limits)
+            ),
+        ))
+    }
+
+    /// Panics unless every argument in `expected` has been used for
+    /// `Mock::call`, in any order.
+    ///
+    /// The panic message lists the arguments that were not used.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("a");
+    ///
+    /// // Panics, listing ["b", "c"] as missing
+    /// mock.assert_called_with_all(&["a", "b", "c"]);
+    /// ```
+    pub fn assert_called_with_all(&self, expected: &[A::Record]) {
+        if let Err(err) = self.verify_called_with_all(expected) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Returns an error unless every argument in `expected` has been used
+    /// for `Mock::call`, in any order.
+    ///
+    /// This is the fallible equivalent of `Mock::assert_called_with_all`.
+    /// The error's `expected` field lists the arguments that were not used.
+    pub fn verify_called_with_all(&self, expected: &[A::Record]) -> Result<(), VerificationError> {
+        let expected = self.normalize_all(expected);
+        let calls = self.calls.read().unwrap();
+        let missing = expected
+            .iter()
+            .filter(|args| !calls.contains(args))
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let debug_limits = *self.debug_limits.read().unwrap();
+        let missing = debug_limits.format_calls(&missing);
+        let message = format!(
+            "mock was never called with {}; its calls were {}",
+            missing,
+            debug_limits.format_calls(&calls)
+        );
+
+        Err(self.verification_error(missing, &calls, message))
+    }
+
+    /// Returns an error unless `args` has been used for `Mock::call`.
+    ///
+    /// The error message compares `args` against each recorded call. See
+    /// `assert_called_with!` for the panicking equivalent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<(&str, u64), ()>::default();
+    /// mock.call(("users", 1));
+    ///
+    /// assert!(mock.verify_called_with(("users", 1)).is_ok());
+    /// assert_eq!(
+    ///     mock.verify_called_with(("users", 2)).unwrap_err().to_string(),
+    ///     "mock was never called with (\"users\", 2):\n\
+    ///      - 0: (\"users\", 2)\n\
+    ///      + 0: (\"users\", 1)"
+    /// );
+    /// ```
+    pub fn verify_called_with<T: Into<A::Record>>(&self, args: T) -> Result<(), VerificationError> {
+        let expected = self.normalize(args.into());
+        let calls = self.calls.read().unwrap();
+
+        if calls.contains(&expected) {
+            return Ok(());
+        }
+
+        let debug_limits = *self.debug_limits.read().unwrap();
+        let each_expected = vec![expected.clone(); calls.len().max(1)];
+        let message = format!(
+            "mock was never called with {}:\n{}",
+            debug_limits.format_args(&expected),
+            diff_calls(&each_expected, &calls, &debug_limits)
+        );
+
+        Err(self.verification_error(debug_limits.format_args(&expected), &calls, message))
+    }
+
+    /// Returns an error unless every expectation registered via a method
+    /// such as `Mock::expect_calls` or `Mock::expect_called_with` has been
+    /// met.
+    ///
+    /// The error reports all of the unmet expectations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let send = Mock::<&str, ()>::default();
+    /// send.expect_calls(2);
+    /// send.expect_called_with("hello");
+    /// send.expect_called_with("goodbye");
+    ///
+    /// send.call("hello");
+    ///
+    /// assert_eq!(
+    ///     send.verify().unwrap_err().to_string(),
+    ///     "mock did not meet 2 of 3 expectations:\n\
+    ///      - expected exactly 2 calls, but it was called 1 time\n\
+    ///      - expected a call with \"goodbye\", but its calls were [\"hello\"]"
+    /// );
+    /// ```
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        let expectations = self.expectations.read().unwrap();
+        let calls = self.calls.read().unwrap();
+        let num_calls = self.num_calls();
+        let debug_limits = *self.debug_limits.read().unwrap();
+
+        let unmet = expectations
+            .iter()
+            .filter_map(|expectation| {
+                expectation
+                    .check(num_calls, &calls, &debug_limits)
+                    .err()
+                    .map(|failure| (expectation.describe(&debug_limits), failure))
+            })
+            .collect::<Vec<_>>();
+
+        if unmet.is_empty() {
+            return Ok(());
+        }
+
+        let expected = unmet
+            .iter()
+            .map(|(description, _)| description.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        let failures = unmet
+            .iter()
+            .map(|(_, failure)| format!("- {}", failure))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let message = format!(
+            "mock did not meet {} of {} expectation{}:\n{}",
+            unmet.len(),
+            expectations.len(),
+            if expectations.len() == 1 { "" } else { "s" },
+            failures
+        );
+
+        Err(self.verification_error(expected, &calls, message))
+    }
+
+    /// Equivalent to `Mock::verify`, for harnesses that look for the
+    /// `verify_result` entry point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    /// mock.expect_called_with(1);
+    ///
+    /// assert!(mock.verify_result().is_err());
+    ///
+    /// mock.call(1);
+    ///
+    /// assert_eq!(mock.verify_result(), Ok(()));
+    /// ```
+    pub fn verify_result(&self) -> Result<(), VerificationError> {
+        self.verify()
+    }
+
+    /// Returns a diff of the call histories of this `Mock` and `other`.
+    ///
+    /// Calls that match are prefixed with two spaces, calls only made to
+    /// this `Mock` with `-` and calls only made to `other` with `+`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let expected = Mock::<u64, ()>::default();
+    /// let actual = Mock::<u64, ()>::default();
+    ///
+    /// expected.call(1);
+    /// expected.call(2);
+    /// actual.call(1);
+    /// actual.call(3);
+    ///
+    /// assert_eq!(expected.calls_diff(&actual), "  0: 1\n- 1: 2\n+ 1: 3");
+    /// ```
+    pub fn calls_diff<D, S, B>(&self, other: &Mock<D, S, B>) -> String
+    where
+        S: Clone,
+        B: ArgRecord<D, Record = A::Record>,
+    {
+        let other_calls = other.calls();
+
+        diff_calls(
+            &self.calls.read().unwrap(),
+            &other_calls,
+            &self.debug_limits.read().unwrap(),
+        )
+    }
+
+    /// Panics unless `other` has the same call history as this `Mock`.
+    ///
+    /// The panic message includes the output of `Mock::calls_diff`.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    ///
+    /// let first = Mock::<&str, ()>::default();
+    /// let second = Mock::<&str, ()>::default();
+    ///
+    /// first.call("a");
+    ///
+    /// first.assert_calls_eq(&second);
+    /// ```
+    pub fn assert_calls_eq<D, S, B>(&self, other: &Mock<D, S, B>)
+    where
+        S: Clone,
+        B: ArgRecord<D, Record = A::Record>,
+    {
+        if let Err(err) = self.verify_calls_eq(other) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Returns an error unless `other` has the same call history as this
+    /// `Mock`.
+    ///
+    /// This is the fallible equivalent of `Mock::assert_calls_eq`. The
+    /// error's `expected` field holds the calls made to `other`.
+    pub fn verify_calls_eq<D, S, B>(&self, other: &Mock<D, S, B>) -> Result<(), VerificationError>
+    where
+        S: Clone,
+        B: ArgRecord<D, Record = A::Record>,
+    {
+        if self.calls_eq(other) {
+            return Ok(());
+        }
+
+        let other_calls = other.calls();
+        let calls = self.calls.read().unwrap();
+        let debug_limits = *self.debug_limits.read().unwrap();
+        let message = format!(
+            "mocks do not have the same call history:\n{}",
+            diff_calls(&calls, &other_calls, &debug_limits)
+        );
+
+        Err(self.verification_error(debug_limits.format_calls(&other_calls), &calls, message))
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<C, R, A> Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: Debug,
+{
+    /// Panics unless every argument used for `Mock::call` satisfies
+    /// `predicate`.
+    ///
+    /// The panic message includes the first call that does not satisfy
+    /// `predicate` and its position in the call history.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<(&str, u64), ()>::default();
+    /// mock.call(("tenant-a", 1));
+    /// mock.call(("tenant-a", 2));
+    ///
+    /// mock.assert_args_all(|&(tenant, _)| tenant == "tenant-a");
+    /// ```
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<(&str, u64), ()>::default();
+    /// mock.call(("tenant-a", 1));
+    /// mock.call(("", 2));
+    ///
+    /// // Panics with: call 1 did not satisfy the predicate: ("", 2)
+    /// mock.assert_args_all(|&(tenant, _)| !tenant.is_empty());
+    /// ```
+    pub fn assert_args_all<F>(&self, predicate: F)
+    where
+        F: Fn(&A::Record) -> bool,
+    {
+        if let Err(err) = self.verify_args_all(predicate) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Returns an error unless every argument used for `Mock::call`
+    /// satisfies `predicate`.
+    ///
+    /// This is the fallible equivalent of `Mock::assert_args_all`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    /// mock.call(2);
+    /// mock.call(-3);
+    ///
+    /// let err = mock.verify_args_all(|&x| x > 0).unwrap_err();
+    ///
+    /// assert_eq!(err.actual, vec!["2".to_string(), "-3".to_string()]);
+    /// assert_eq!(err.to_string(), "call 1 did not satisfy the predicate: -3");
+    /// ```
+    pub fn verify_args_all<F>(&self, predicate: F) -> Result<(), VerificationError>
+    where
+        F: Fn(&A::Record) -> bool,
+    {
+        let calls = self.calls.read().unwrap();
+
+        match calls.iter().enumerate().find(|&(_, args)| !predicate(args)) {
+            Some((i, args)) => {
+                let message = format!(
+                    "call {} did not satisfy the predicate: {}",
+                    i,
+                    self.debug_args(args)
+                );
+
+                Err(self.verification_error(
+                    "arguments satisfying the predicate".to_string(),
+                    &calls,
+                    message,
+                ))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns an error unless `Mock::call` has been called.
+    ///
+    /// See `assert_called!` for the panicking equivalent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<i64, ()>::default();
+    ///
+    /// assert_eq!(mock.verify_called().unwrap_err().to_string(), "mock was never called");
+    ///
+    /// mock.call(1);
+    ///
+    /// assert!(mock.verify_called().is_ok());
+    /// ```
+    pub fn verify_called(&self) -> Result<(), VerificationError> {
+        if self.called() {
+            return Ok(());
+        }
+
+        let calls = self.calls.read().unwrap();
+
+        Err(self.verification_error(
+            "at least one call".to_string(),
+            &calls,
+            "mock was never called".to_string(),
+        ))
+    }
+
+    pub(crate) fn debug_args(&self, args: &A::Record) -> String {
+        self.debug_limits.read().unwrap().format_args(args)
+    }
+
+    /// Describes the behavior that `Mock::call` currently uses, ignoring
+    /// values set for specific calls.
+    fn describe_behavior(&self) -> &'static str {
+        if self.delegating.load(Ordering::SeqCst) && self.delegate.read().unwrap().is_some() {
+            return "the function set via delegate_to";
+        }
+
+        let resolution = match *self.resolution.read().unwrap() {
+            Resolution::PanicOnAmbiguity => Resolution::MostSpecific,
+            resolution => resolution,
+        };
+
+        match resolution.resolve(&self.configured.read().unwrap()) {
+            Behavior::Conditional => "return values for specific arguments",
+            Behavior::Sequence => "a sequence of return values",
+            Behavior::Function if self.mock_fn.read().unwrap().is_some() => {
+                "the function set via use_fn"
+            }
+            Behavior::Function if self.mock_closure.read().unwrap().is_some() => {
+                "the closure set via use_closure"
+            }
+            Behavior::Function => "the closure set via use_indexed_closure",
+            Behavior::Value => "its return value",
+        }
+    }
+
+    pub(crate) fn debug_calls(&self) -> String {
+        let calls = self.calls.read().unwrap();
+        self.debug_limits.read().unwrap().format_calls(&calls)
+    }
+
+    fn verification_error(
+        &self,
+        expected: String,
+        calls: &[A::Record],
+        message: String,
+    ) -> VerificationError {
+        VerificationError {
+            mock_name: self.name.read().unwrap().clone(),
+            expected,
+            actual: calls.iter().map(|args| self.debug_args(args)).collect(),
+            num_calls: self.num_calls(),
+            message,
+        }
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<C, R, A> Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: Hash + Eq + Send + Sync + 'static,
+{
+    /// Maintain a hash index of the recorded arguments so that
+    /// `Mock::called_with` and `Mock::call_count_with` take constant time
+    /// rather than scanning the whole call history.
+    ///
+    /// Calls made before indexing is enabled are included in the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<u64, ()>::default();
+    /// mock.call(1);
+    ///
+    /// mock.index_calls();
+    ///
+    /// for i in 0..1000 {
+    ///     mock.call(i % 10);
+    ///     assert!(mock.called_with(i % 10));
+    /// }
+    ///
+    /// assert_eq!(mock.call_count_with(1u64), 101);
+    /// ```
+    pub fn index_calls(&self) {
+        let mut call_index = self.call_index.write().unwrap();
+
+        if call_index.is_some() {
+            return;
+        }
+
+        let mut index = HashMap::new();
+
+        for record in self.calls.read().unwrap().iter() {
+            CallIndex::insert(&mut index, record);
+        }
+
+        *call_index = Some(Box::new(index))
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<C, R, A> Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: Hash + Eq,
+{
+    /// Returns summary statistics of the call history, including how often
+    /// each distinct argument was used and, if timestamps are being recorded,
+    /// when the first and last calls were made.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let cache_miss = Mock::<&str, ()>::default();
+    /// cache_miss.record_timestamps();
+    /// cache_miss.call("a");
+    /// cache_miss.call("b");
+    /// cache_miss.call("a");
+    ///
+    /// let stats = cache_miss.stats();
+    ///
+    /// assert_eq!(stats.num_calls, 3);
+    /// assert_eq!(stats.num_distinct, 2);
+    /// assert_eq!(stats.frequency(&"a"), 2);
+    /// assert_eq!(stats.frequency(&"c"), 0);
+    /// assert!(stats.first_call.unwrap() <= stats.last_call.unwrap());
+    /// ```
+    pub fn stats(&self) -> CallStats<A::Record> {
+        let calls = self.calls.read().unwrap();
+        let timestamps = self.timestamps.read().unwrap();
+        let timestamps = timestamps.as_ref().map_or(&[][..], |t| &t[..]);
+
+        CallStats::new(self.num_calls(), &calls, timestamps)
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<C, R, A> Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: AsRef<str>,
+{
+    /// Returns true if `Mock::call` has been called with an argument that
+    /// contains `pattern`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<String, ()>::default();
+    /// mock.call("failed to open /tmp/data".to_string());
+    ///
+    /// assert!(mock.called_with_substring("/tmp/data"));
+    /// assert!(!mock.called_with_substring("/var"));
+    /// ```
+    pub fn called_with_substring(&self, pattern: &str) -> bool {
+        self.calls
+            .read()
+            .unwrap()
+            .iter()
+            .any(|args| args.as_ref().contains(pattern))
+    }
+
+    /// Returns true if `Mock::call` has been called with an argument that
+    /// starts with `prefix`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("/tmp/data");
+    ///
+    /// assert!(mock.called_with_prefix("/tmp"));
+    /// assert!(!mock.called_with_prefix("data"));
+    /// ```
+    pub fn called_with_prefix(&self, prefix: &str) -> bool {
+        self.calls
+            .read()
+            .unwrap()
+            .iter()
+            .any(|args| args.as_ref().starts_with(prefix))
+    }
+
+    /// Case-insensitive version of `Mock::called_with_substring`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("Connection REFUSED");
+    ///
+    /// assert!(mock.called_with_substring_ignore_case("refused"));
+    /// assert!(!mock.called_with_substring("refused"));
+    /// ```
+    pub fn called_with_substring_ignore_case(&self, pattern: &str) -> bool {
+        let pattern = pattern.to_lowercase();
+        self.calls
+            .read()
+            .unwrap()
+            .iter()
+            .any(|args| args.as_ref().to_lowercase().contains(&pattern))
+    }
+
+    /// Case-insensitive version of `Mock::called_with_prefix`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<&str, ()>::default();
+    /// mock.call("ERROR: disk full");
+    ///
+    /// assert!(mock.called_with_prefix_ignore_case("error:"));
+    /// assert!(!mock.called_with_prefix("error:"));
+    /// ```
+    pub fn called_with_prefix_ignore_case(&self, prefix: &str) -> bool {
+        let prefix = prefix.to_lowercase();
+        self.calls
+            .read()
+            .unwrap()
+            .iter()
+            .any(|args| args.as_ref().to_lowercase().starts_with(&prefix))
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<C, R, A> Mock<C, R, A>
+where
+    C: Clone + 'static,
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    /// Also record every call in `log`, converted via `to_call`.
+    ///
+    /// This allows the calls of several `Mock`s to be inspected as one
+    /// ordered history. See `CallLog` for more details. Registering with
+    /// another `CallLog` replaces the previous one.
+    pub fn log_calls_to<E>(&self, log: &CallLog<E>, to_call: fn(C) -> E)
+    where
+        E: Send + Sync + 'static,
+    {
+        let log = log.clone();
+        let log_call = move |args: &C| log.push(to_call(args.clone()));
+
+        *self.call_log.write().unwrap() = Some(Box::new(log_call))
+    }
+
+    /// Send a clone of the arguments of every subsequent call to `sender`,
+    /// so that a test can wait for calls made on other threads as they
+    /// happen.
+    ///
+    /// Arguments are sent whether or not calls are being recorded, and are
+    /// silently dropped once the receiver has been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::mpsc;
+    /// use std::thread;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let (tx, rx) = mpsc::channel();
+    /// let notify = Mock::<String, ()>::default();
+    /// notify.capture_args_into(tx);
+    ///
+    /// let worker = {
+    ///     let notify = notify.clone();
+    ///     thread::spawn(move || notify.call("job done".to_string()))
+    /// };
+    ///
+    /// assert_eq!(rx.recv(), Ok("job done".to_string()));
+    /// worker.join().unwrap();
+    /// ```
+    pub fn capture_args_into(&self, sender: Sender<C>)
+    where
+        C: Send,
+    {
+        let sender = Mutex::new(sender);
+
+        self.on_call(Box::new(move |args| {
+            let sender = sender.lock().unwrap_or_else(|err| err.into_inner());
+            let _ = sender.send(args.clone());
+        }))
+    }
+
+    /// Returns a `Receiver` that is sent a clone of the arguments of every
+    /// subsequent call, so that a test can block until a specific call is
+    /// made on another thread.
+    ///
+    /// See `Mock::capture_args_into` for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    ///
+    /// use pseudo::Mock;
+    ///
+    /// let publish = Mock::<(&str, u64), ()>::default();
+    /// let calls = publish.subscribe();
+    ///
+    /// {
+    ///     let publish = publish.clone();
+    ///     thread::spawn(move || {
+    ///         for i in 0..3 {
+    ///             publish.call(("progress", i));
+    ///         }
+    ///
+    ///         publish.call(("done", 3));
+    ///     });
+    /// }
+    ///
+    /// let done = calls
+    ///     .iter()
+    ///     .find(|&(event, _)| event == "done")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(done, ("done", 3));
+    /// assert_eq!(publish.num_calls(), 4);
+    /// ```
+    pub fn subscribe(&self) -> Receiver<C>
+    where
+        C: Send,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.capture_args_into(sender);
+        receiver
+    }
+}
+
+impl<C, S, A> Mock<C, Option<S>, A>
+where
     S: Clone,
+    A: ArgRecord<C>,
 {
     /// Return `Some(return_value)` from `Mock::call`.
     ///
@@ -355,13 +4314,32 @@ where
     pub fn return_none(&self) {
         self.return_value(None)
     }
+
+    /// Return `Some(return_value)` from the next `n` calls, then `None` from
+    /// every call after that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let next_page = Mock::<(), Option<&str>>::default();
+    /// next_page.return_some_times(2, "page");
+    ///
+    /// assert_eq!(next_page.call(()), Some("page"));
+    /// assert_eq!(next_page.call(()), Some("page"));
+    /// assert_eq!(next_page.call(()), None);
+    /// ```
+    pub fn return_some_times<T: Into<S>>(&self, n: usize, return_value: T) {
+        self.switch_after(n, Some(return_value.into()), None)
+    }
 }
 
-impl<C, O, E> Mock<C, Result<O, E>>
+impl<C, O, E, A> Mock<C, Result<O, E>, A>
 where
-    C: Clone,
     O: Clone,
     E: Clone,
+    A: ArgRecord<C>,
 {
     /// Return `Ok(return_value)` from `Mock::call`.
     ///
@@ -394,17 +4372,360 @@ where
     pub fn return_err<T: Into<E>>(&self, return_value: T) {
         self.return_value(Err(return_value.into()))
     }
+
+    /// Return `Ok(ok)` from the next `n` calls, then `Err(err)` from every
+    /// call after that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let send = Mock::<&str, Result<(), &str>>::new(Ok(()));
+    /// send.return_ok_until(2, (), "rate limited");
+    ///
+    /// assert_eq!(send.call("a"), Ok(()));
+    /// assert_eq!(send.call("b"), Ok(()));
+    /// assert_eq!(send.call("c"), Err("rate limited"));
+    /// assert_eq!(send.call("d"), Err("rate limited"));
+    /// ```
+    pub fn return_ok_until<T: Into<O>, U: Into<E>>(&self, n: usize, ok: T, err: U) {
+        self.switch_after(n, Ok(ok.into()), Err(err.into()))
+    }
+
+    /// Return `Ok(ok)` from the next call, then `Err(err)` from every call
+    /// after that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let connect = Mock::<(), Result<&str, &str>>::new(Ok(""));
+    /// connect.return_ok_then_err("connected", "connection refused");
+    ///
+    /// assert_eq!(connect.call(()), Ok("connected"));
+    /// assert_eq!(connect.call(()), Err("connection refused"));
+    /// ```
+    pub fn return_ok_then_err<T: Into<O>, U: Into<E>>(&self, ok: T, err: U) {
+        self.return_ok_until(1, ok, err)
+    }
+
+    /// Return each of `results` in order on successive calls, then keep
+    /// returning the last one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let fetch = Mock::<&str, Result<&str, &str>>::new(Ok(""));
+    /// fetch.return_results(vec![Err("timeout"), Err("timeout"), Ok("body")]);
+    ///
+    /// assert_eq!(fetch.call("/"), Err("timeout"));
+    /// assert_eq!(fetch.call("/"), Err("timeout"));
+    /// assert_eq!(fetch.call("/"), Ok("body"));
+    /// ```
+    pub fn return_results(&self, results: Vec<Result<O, E>>) {
+        self.return_values(results)
+    }
 }
 
-impl<C, R> Debug for Mock<C, R>
+impl<C, O, E, A> Mock<C, Result<O, E>, A>
+where
+    C: PartialEq + Send + Sync + 'static,
+    O: Clone,
+    E: Clone,
+    A: ArgRecord<C>,
+{
+    /// Return `Err(err)` whenever `Mock::call` is called with `args`,
+    /// falling back to the `Mock`'s other behavior for other arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let save = Mock::<u64, Result<(), String>>::new(Ok(()));
+    /// save.return_err_for(3, "invalid record");
+    ///
+    /// let results = (1..5).map(|id| save.call(id)).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     results,
+    ///     vec![Ok(()), Ok(()), Err("invalid record".to_string()), Ok(())]
+    /// );
+    /// ```
+    pub fn return_err_for<T: Into<E>>(&self, args: C, err: T) {
+        self.return_value_for(args, Err(err.into()))
+    }
+}
+
+impl<C, T, A> Mock<C, DropTracked<T>, A>
+where
+    T: Clone,
+    A: ArgRecord<C>,
+{
+    /// Returns the number of values returned from the current return value
+    /// that have since been dropped.
+    ///
+    /// See `DropTracked` for more details.
+    pub fn returned_values_dropped(&self) -> usize {
+        self.with_return_value(DropTracked::num_dropped)
+    }
+
+    /// Returns the number of values returned from the current return value
+    /// that have not yet been dropped.
+    pub fn returned_values_alive(&self) -> usize {
+        self.with_return_value(|return_value| {
+            return_value.num_returned() - return_value.num_dropped()
+        })
+    }
+}
+
+/// Returns a duration between `min` and `max` picked by `random`, or `min` if
+/// `max` is not greater than it.
+fn jitter(min: Duration, max: Duration, random: u128) -> Duration {
+    if max <= min {
+        return min;
+    }
+
+    let offset = random % ((max - min).as_nanos() + 1);
+    let offset = Duration::new(
+        (offset / 1_000_000_000) as u64,
+        (offset % 1_000_000_000) as u32,
+    );
+
+    min.checked_add(offset).unwrap_or(max)
+}
+
+/// A xorshift generator, which is plenty for spreading out delays and avoids
+/// hashing on every call.
+struct XorShift(u64);
+
+impl XorShift {
+    fn seeded() -> Self {
+        let seed = RandomState::new().build_hasher().finish();
+
+        // Zero is the only state that xorshift never leaves.
+        XorShift(seed | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn next(&mut self) -> u128 {
+        (u128::from(self.next_u64()) << 64) | u128::from(self.next_u64())
+    }
+}
+
+impl<C, R, A> Clone for Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C> + Clone,
+{
+    fn clone(&self) -> Self {
+        Mock {
+            return_value: self.return_value.clone(),
+            initial_return_value: self.initial_return_value.clone(),
+            lazy_init: self.lazy_init.clone(),
+            mock_fn: self.mock_fn.clone(),
+            mock_closure: self.mock_closure.clone(),
+            mock_indexed_closure: self.mock_indexed_closure.clone(),
+            delegate: self.delegate.clone(),
+            delegating: self.delegating.clone(),
+            sequence: self.sequence.clone(),
+            once: self.once.clone(),
+            nth: self.nth.clone(),
+            overrides: self.overrides.clone(),
+            stubs: self.stubs.clone(),
+            strict: self.strict.clone(),
+            resolution: self.resolution.clone(),
+            configured: self.configured.clone(),
+            context: self.context.clone(),
+            delay: self.delay.clone(),
+            rng: self.rng.clone(),
+            #[cfg(not(feature = "disabled"))]
+            name: self.name.clone(),
+            arg_record: self.arg_record.clone(),
+            #[cfg(not(feature = "disabled"))]
+            calls: self.calls.clone(),
+            num_calls: self.num_calls.clone(),
+            #[cfg(not(feature = "disabled"))]
+            call_signal: self.call_signal.clone(),
+            #[cfg(not(feature = "disabled"))]
+            waiters: self.waiters.clone(),
+            #[cfg(not(feature = "disabled"))]
+            recording_disabled: self.recording_disabled.clone(),
+            #[cfg(not(feature = "disabled"))]
+            sample_every: self.sample_every.clone(),
+            #[cfg(not(feature = "disabled"))]
+            history_capacity: self.history_capacity.clone(),
+            #[cfg(not(feature = "disabled"))]
+            call_index: self.call_index.clone(),
+            #[cfg(not(feature = "disabled"))]
+            timestamps: self.timestamps.clone(),
+            #[cfg(not(feature = "disabled"))]
+            sequences: self.sequences.clone(),
+            #[cfg(not(feature = "disabled"))]
+            threads: self.threads.clone(),
+            #[cfg(not(feature = "disabled"))]
+            sources: self.sources.clone(),
+            #[cfg(not(feature = "disabled"))]
+            returned: self.returned.clone(),
+            #[cfg(not(feature = "disabled"))]
+            expectations: self.expectations.clone(),
+            #[cfg(not(feature = "disabled"))]
+            call_log: self.call_log.clone(),
+            #[cfg(not(feature = "disabled"))]
+            observers: self.observers.clone(),
+            #[cfg(not(feature = "disabled"))]
+            observed: self.observed.clone(),
+            #[cfg(not(feature = "disabled"))]
+            normalizer: self.normalizer.clone(),
+            #[cfg(not(feature = "disabled"))]
+            debug_limits: self.debug_limits.clone(),
+            return_refs: self.return_refs.clone(),
+        }
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "disabled")))]
+impl<C, R, A> Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: Serialize,
+{
+    /// Returns the `Mock`'s name, total number of calls and recorded calls
+    /// as JSON, for exporting to test artifacts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Mock;
+    ///
+    /// let fetch = Mock::<(String, u64), ()>::default();
+    /// fetch.set_name("fetch");
+    /// fetch.call(("users".to_string(), 1));
+    ///
+    /// assert_eq!(
+    ///     fetch.calls_json().unwrap(),
+    ///     r#"{"name":"fetch","num_calls":1,"calls":[["users",1]]}"#
+    /// );
+    /// ```
+    pub fn calls_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "disabled")))]
+impl<C, R, A> Serialize for Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Mock", 3)?;
+        state.serialize_field("name", &*self.name.read().unwrap())?;
+        state.serialize_field("num_calls", &self.num_calls())?;
+        state.serialize_field("calls", &*self.calls.read().unwrap())?;
+        state.end()
+    }
+}
+
+impl<C, R, A> Debug for Mock<C, R, A>
 where
-    C: Clone + Debug,
     R: Clone + Debug,
+    A: ArgRecord<C>,
+    A::Record: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("Mock");
+        match *self.return_value.read().unwrap() {
+            Some(ref return_value) => debug.field("return_value", return_value),
+            None => debug.field("return_value", &Formatted("<lazy>".to_string())),
+        };
+
+        if let Some(ref sequence) = *self.sequence.read().unwrap() {
+            debug.field("sequence", sequence);
+        }
+
+        #[cfg(not(feature = "disabled"))]
+        debug.field("calls", &Formatted(self.debug_calls()));
+
+        debug.finish()
+    }
+}
+
+/// Renders the `Mock`'s name, current behavior and a numbered listing of its
+/// recorded calls, along with the behavior that served each call.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::Mock;
+///
+/// let fetch = Mock::<(&str, u64), Option<&str>>::default();
+/// fetch.set_name("fetch");
+/// fetch.call(("users", 1));
+/// fetch.return_value_for(("users", 2), Some("bob"));
+/// fetch.call(("users", 2));
+///
+/// assert_eq!(
+///     fetch.to_string(),
+///     "fetch: 2 calls, using return values for specific arguments\n\
+///      \x20 0: (\"users\", 1) <- Value\n\
+///      \x20 1: (\"users\", 2) <- Stub"
+/// );
+/// ```
+#[cfg(not(feature = "disabled"))]
+impl<C, R, A> Display for Mock<C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+    A::Record: Debug,
 {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        f.debug_struct("Mock")
-            .field("return_value", &self.return_value)
-            .field("calls", &self.calls)
-            .finish()
+        let num_calls = self.num_calls();
+        let name = self.name.read().unwrap();
+
+        write!(
+            f,
+            "{}: {} call{}, using {}",
+            name.as_ref().map_or("mock", |name| name),
+            num_calls,
+            if num_calls == 1 { "" } else { "s" },
+            self.describe_behavior()
+        )?;
+
+        let calls = self.calls.read().unwrap();
+        let sequences = self.sequences.read().unwrap();
+        let sources = self.sources.read().unwrap();
+        let debug_limits = *self.debug_limits.read().unwrap();
+        let shown = debug_limits.max_calls.unwrap_or(usize::MAX);
+        let listed = calls.iter().zip(sequences.iter()).take(shown);
+
+        for (i, (args, sequence)) in listed.enumerate() {
+            write!(f, "\n  {}: {}", i, debug_limits.format_args(args))?;
+
+            if let Some(source) = sources.get(sequence) {
+                write!(f, " <- {:?}", source)?;
+            }
+        }
+
+        if calls.len() > shown {
+            write!(f, "\n  ... ({} more calls)", calls.len() - shown)?;
+        }
+
+        Ok(())
     }
 }
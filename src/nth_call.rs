@@ -0,0 +1,43 @@
+use arg_record::ArgRecord;
+use mock::Mock;
+
+/// Overrides what a `Mock` does on a single call, returned by
+/// `Mock::on_nth_call`.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::Mock;
+///
+/// let connect = Mock::<(), Result<(), &str>>::new(Err("timed out"));
+/// connect.on_nth_call(2).return_value(Ok(()));
+///
+/// assert_eq!(connect.call(()), Err("timed out"));
+/// assert_eq!(connect.call(()), Err("timed out"));
+/// assert_eq!(connect.call(()), Ok(()));
+/// assert_eq!(connect.call(()), Err("timed out"));
+/// ```
+#[must_use = "`NthCall` does nothing until `NthCall::return_value` is called"]
+pub struct NthCall<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    mock: &'a Mock<C, R, A>,
+    n: usize,
+}
+
+impl<'a, C, R, A> NthCall<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    pub(crate) fn new(mock: &'a Mock<C, R, A>, n: usize) -> Self {
+        NthCall { mock, n }
+    }
+
+    /// Return `return_value` from the call.
+    pub fn return_value<T: Into<R>>(self, return_value: T) {
+        self.mock.set_nth_return_value(self.n, return_value.into())
+    }
+}
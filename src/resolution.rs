@@ -0,0 +1,78 @@
+/// Controls which behavior a `Mock` uses when more than one has been
+/// configured.
+///
+/// A `Mock` always has a return value, and may also have a function or
+/// closure set via `Mock::use_fn` or `Mock::use_closure`, a sequence of
+/// return values set via `Mock::return_values` and return values for
+/// specific arguments set via `Mock::return_value_for` or `Mock::when`. By default, the most
+/// specific behavior wins, in that order from least to most specific.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{Mock, Resolution};
+///
+/// let mock = Mock::<i64, i64>::new(0);
+/// mock.resolve_with(Resolution::LastConfigured);
+///
+/// mock.use_fn(|x| x * 2);
+/// assert_eq!(mock.call(4), 8);
+///
+/// mock.return_value(-1);
+/// assert_eq!(mock.call(4), -1);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Resolution {
+    /// A return value for specific arguments wins over a sequence of return
+    /// values, which wins over a function or closure, which wins over the
+    /// return value, regardless of the order in which they were configured.
+    /// This is the default.
+    #[default]
+    MostSpecific,
+    /// Whichever behavior was configured most recently wins.
+    LastConfigured,
+    /// Whichever behavior was configured first wins. The return value given
+    /// at construction time only wins if nothing else has been configured.
+    FirstConfigured,
+    /// Panic on `Mock::call` if more than one kind of behavior has been
+    /// configured since construction.
+    PanicOnAmbiguity,
+}
+
+/// The kinds of behavior that a `Mock` can be configured with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Behavior {
+    Value,
+    Function,
+    Sequence,
+    Conditional,
+}
+
+impl Resolution {
+    /// Picks the behavior to use, given the behaviors configured since
+    /// construction in order from first to last.
+    pub(crate) fn resolve(self, configured: &[Behavior]) -> Behavior {
+        match self {
+            Resolution::MostSpecific => [
+                Behavior::Conditional,
+                Behavior::Sequence,
+                Behavior::Function,
+            ]
+            .iter()
+            .cloned()
+            .find(|behavior| configured.contains(behavior))
+            .unwrap_or(Behavior::Value),
+            Resolution::LastConfigured => *configured.last().unwrap_or(&Behavior::Value),
+            Resolution::FirstConfigured => *configured.first().unwrap_or(&Behavior::Value),
+            Resolution::PanicOnAmbiguity => {
+                let first = configured.first();
+
+                if configured.iter().any(|behavior| Some(behavior) != first) {
+                    panic!("mock has more than one kind of behavior configured");
+                }
+
+                Resolution::MostSpecific.resolve(configured)
+            }
+        }
+    }
+}
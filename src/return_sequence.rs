@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+
+use arg_record::ArgRecord;
+use mock::Mock;
+
+/// Controls what a `Mock` does once a sequence of return values set via
+/// `Mock::return_values` or `Mock::use_iterator` has been used up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Exhaustion {
+    /// Keep returning the last value of the sequence. This is the default.
+    #[default]
+    RepeatLast,
+    /// Fall back to the `Mock`'s other behavior, such as its return value or
+    /// a function set via `Mock::use_fn`.
+    Fallback,
+    /// Panic on `Mock::call`.
+    Panic,
+    /// Start again from the first value of the sequence.
+    Cycle,
+}
+
+/// A single step of a sequence set via `Mock::return_values` or
+/// `Mock::first_returns`.
+#[derive(Clone, Debug)]
+pub(crate) enum Stage<R> {
+    Return(R),
+    Panic(String),
+}
+
+type Source<R> = Mutex<Box<dyn Iterator<Item = R> + Send>>;
+
+/// The remaining stages of a sequence set via `Mock::return_values`,
+/// `Mock::use_iterator` or `Mock::first_returns`.
+pub(crate) struct ReturnSequence<R> {
+    num_returned: usize,
+    remaining: VecDeque<Stage<R>>,
+    exhaustion: Exhaustion,
+    source: Option<Source<R>>,
+    recycled: VecDeque<Stage<R>>,
+}
+
+impl<R> ReturnSequence<R>
+where
+    R: Clone,
+{
+    pub(crate) fn new(values: Vec<R>, exhaustion: Exhaustion) -> Self {
+        ReturnSequence {
+            num_returned: 0,
+            remaining: values.into_iter().map(Stage::Return).collect(),
+            exhaustion,
+            source: None,
+            recycled: VecDeque::new(),
+        }
+    }
+
+    /// Creates a sequence that draws its values from `source` as they are
+    /// needed.
+    pub(crate) fn from_iter(
+        source: Box<dyn Iterator<Item = R> + Send>,
+        exhaustion: Exhaustion,
+    ) -> Self {
+        ReturnSequence {
+            source: Some(Mutex::new(source)),
+            ..Self::new(vec![], exhaustion)
+        }
+    }
+
+    pub(crate) fn push(&mut self, stage: Stage<R>) {
+        self.remaining.push_back(stage)
+    }
+
+    /// Returns the next value of the sequence, or `None` if it has been used
+    /// up and the `Mock` should fall back to its other behavior.
+    ///
+    /// Returns an error if the next stage is a panic, or if the sequence has
+    /// been used up and its exhaustion policy is `Exhaustion::Panic`, so that
+    /// the `Mock` can panic without poisoning its lock on the sequence.
+    pub(crate) fn next(&mut self) -> Result<Option<R>, String> {
+        self.draw();
+
+        let next = match (self.remaining.len(), self.exhaustion) {
+            (1, Exhaustion::RepeatLast) => self.remaining.front().cloned(),
+            (0, Exhaustion::Panic) => {
+                return Err(format!(
+                    "mock's sequence of return values was exhausted after {} calls",
+                    self.num_returned
+                ))
+            }
+            (_, Exhaustion::Cycle) => {
+                let next = self.remaining.pop_front();
+
+                if self.source.is_some() {
+                    self.recycled.extend(next.clone());
+                } else {
+                    self.remaining.extend(next.clone());
+                }
+
+                next
+            }
+            _ => self.remaining.pop_front(),
+        };
+
+        if next.is_some() {
+            self.num_returned += 1;
+        }
+
+        match next {
+            Some(Stage::Return(return_value)) => Ok(Some(return_value)),
+            Some(Stage::Panic(message)) => Err(message),
+            None => Ok(None),
+        }
+    }
+
+    /// Draws values from the source so that at least two are remaining, if
+    /// possible, which lets `Exhaustion::RepeatLast` tell the last value
+    /// apart from the others.
+    fn draw(&mut self) {
+        let mut exhausted = false;
+
+        if let Some(ref mut source) = self.source {
+            let source = source.get_mut().unwrap_or_else(|err| err.into_inner());
+
+            while self.remaining.len() < 2 {
+                match source.next() {
+                    Some(return_value) => self.remaining.push_back(Stage::Return(return_value)),
+                    None => {
+                        exhausted = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if exhausted {
+            self.source = None;
+            self.remaining.append(&mut self.recycled);
+        }
+    }
+}
+
+impl<R> Debug for ReturnSequence<R>
+where
+    R: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("ReturnSequence")
+            .field("num_returned", &self.num_returned)
+            .field("remaining", &self.remaining)
+            .field("exhaustion", &self.exhaustion)
+            .finish()
+    }
+}
+
+/// Adds stages to the sequence started by `Mock::first_returns`.
+///
+/// Each stage is used for one call, and the last stage is repeated once the
+/// others have been used up. The `Debug` output of the `Mock` shows the
+/// stages that remain, so failures explain which stage was active.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::Mock;
+///
+/// let mock = Mock::<(), u64>::default();
+/// mock.first_returns(1).then_returns(2).then_panics("boom");
+///
+/// assert_eq!(mock.call(()), 1);
+/// assert_eq!(mock.call(()), 2);
+/// assert!(format!("{:?}", mock).contains(r#"remaining: [Panic("boom")]"#));
+/// ```
+pub struct Stages<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    mock: &'a Mock<C, R, A>,
+}
+
+impl<'a, C, R, A> Stages<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    pub(crate) fn new(mock: &'a Mock<C, R, A>) -> Self {
+        Stages { mock }
+    }
+
+    /// Return `return_value` from the call after the previous stage.
+    pub fn then_returns(self, return_value: R) -> Self {
+        self.mock.push_stage(Stage::Return(return_value));
+        self
+    }
+
+    /// Panic with `message` on the call after the previous stage.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use pseudo::Mock;
+    ///
+    /// let mock = Mock::<(), u64>::default();
+    /// mock.first_returns(1).then_panics("connection reset");
+    ///
+    /// mock.call(());
+    ///
+    /// // Panics with: connection reset
+    /// mock.call(());
+    /// ```
+    pub fn then_panics(self, message: &str) -> Self {
+        self.mock.push_stage(Stage::Panic(message.to_string()));
+        self
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::{Arc, RwLock};
+
+use verification_error::VerificationError;
+
+type OptionalRef<T> = Arc<RwLock<Option<T>>>;
+
+/// An expected order of calls across several `Mock`s.
+///
+/// Each `Mock` only tracks its own calls, so checking that one method was
+/// called before another would otherwise mean comparing timestamps or
+/// sequence numbers by hand. Mocks join a `Sequence` via
+/// `Mock::in_sequence`, in the order in which they are expected to be
+/// called, and `Sequence::verify` checks that every member was called and
+/// that no member was called after a later one. Repeated calls to the same
+/// member in a row are allowed.
+///
+/// Members are identified in failure messages by the name given with
+/// `Mock::set_name`, or by their position in the sequence otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{Mock, Sequence};
+///
+/// let seq = Sequence::new();
+///
+/// let connect = Mock::<String, ()>::default();
+/// let send = Mock::<Vec<u8>, ()>::default();
+/// let close = Mock::<(), ()>::default();
+///
+/// connect.set_name("connect");
+/// send.set_name("send");
+/// close.set_name("close");
+///
+/// connect.in_sequence(&seq);
+/// send.in_sequence(&seq);
+/// close.in_sequence(&seq);
+///
+/// connect.call("localhost".to_string());
+/// send.call(vec![1]);
+/// send.call(vec![2]);
+/// close.call(());
+///
+/// assert!(seq.verify().is_ok());
+///
+/// seq.reset_calls();
+/// send.call(vec![3]);
+/// connect.call("localhost".to_string());
+///
+/// assert_eq!(
+///     seq.verify().unwrap_err().to_string(),
+///     "expected calls in the order [connect, send, close], but they were made in the order \
+///      [send, connect]"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Sequence {
+    members: Arc<RwLock<Vec<OptionalRef<String>>>>,
+    calls: Arc<RwLock<Vec<usize>>>,
+}
+
+impl Sequence {
+    /// Creates a new `Sequence` without any members.
+    pub fn new() -> Self {
+        Sequence {
+            members: Arc::new(RwLock::new(vec![])),
+            calls: Arc::new(RwLock::new(vec![])),
+        }
+    }
+
+    /// Returns the number of calls made to all members.
+    pub fn num_calls(&self) -> usize {
+        self.calls.read().unwrap().len()
+    }
+
+    /// Returns true if the members have been called in the expected order.
+    pub fn called_in_order(&self) -> bool {
+        let members = self.members.read().unwrap().len();
+        let calls = self.calls.read().unwrap();
+        let mut steps = calls.clone();
+        steps.dedup();
+
+        steps.into_iter().eq(0..members)
+    }
+
+    /// Returns an error describing the actual order of calls if the members
+    /// have not been called in the expected order.
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        if self.called_in_order() {
+            return Ok(());
+        }
+
+        let labels = self.labels();
+        let actual: Vec<String> = self
+            .calls
+            .read()
+            .unwrap()
+            .iter()
+            .map(|&member| labels[member].clone())
+            .collect();
+        let expected = format!("[{}]", labels.join(", "));
+        let message = format!(
+            "expected calls in the order {}, but they were made in the order [{}]",
+            expected,
+            actual.join(", ")
+        );
+
+        Err(VerificationError {
+            mock_name: None,
+            expected,
+            num_calls: actual.len(),
+            actual,
+            message,
+        })
+    }
+
+    /// Reset the calls made to all members, keeping the members themselves.
+    pub fn reset_calls(&self) {
+        self.calls.write().unwrap().clear()
+    }
+
+    pub(crate) fn join(&self, name: OptionalRef<String>) -> usize {
+        let mut members = self.members.write().unwrap();
+        members.push(name);
+        members.len() - 1
+    }
+
+    pub(crate) fn push(&self, member: usize) {
+        self.calls.write().unwrap().push(member)
+    }
+
+    fn labels(&self) -> Vec<String> {
+        self.members
+            .read()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(i, name)| match *name.read().unwrap() {
+                Some(ref name) => name.clone(),
+                None => format!("mock #{}", i + 1),
+            })
+            .collect()
+    }
+}
+
+impl Clone for Sequence {
+    fn clone(&self) -> Self {
+        Sequence {
+            members: self.members.clone(),
+            calls: self.calls.clone(),
+        }
+    }
+}
+
+impl Default for Sequence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, RwLock};
+
+type OptionalRef<T> = Arc<RwLock<Option<T>>>;
+
+/// Records calls that are forwarded to a real implementation.
+///
+/// Where `Mock` replaces a method, `Spy` observes it: each call is passed on
+/// to the real implementation and its arguments, and optionally its results,
+/// are tracked using the same history methods as `Mock`.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::Spy;
+///
+/// trait Calculator {
+///     fn add(&self, x: i64, y: i64) -> i64;
+/// }
+///
+/// struct RealCalculator;
+///
+/// impl Calculator for RealCalculator {
+///     fn add(&self, x: i64, y: i64) -> i64 {
+///         x + y
+///     }
+/// }
+///
+/// struct SpyCalculator {
+///     real: RealCalculator,
+///     add: Spy<(i64, i64), i64>,
+/// }
+///
+/// impl Calculator for SpyCalculator {
+///     fn add(&self, x: i64, y: i64) -> i64 {
+///         self.add.call((x, y), |(x, y)| self.real.add(x, y))
+///     }
+/// }
+///
+/// let spy = SpyCalculator { real: RealCalculator, add: Spy::new() };
+/// spy.add.record_results();
+///
+/// assert_eq!(spy.add(1, 2), 3);
+///
+/// assert!(spy.add.called_with((1, 2)));
+/// assert_eq!(spy.add.results(), vec![3]);
+/// ```
+#[derive(Clone)]
+pub struct Spy<C, R>
+where
+    C: Clone,
+    R: Clone,
+{
+    calls: Arc<RwLock<Vec<C>>>,
+    results: OptionalRef<Vec<R>>,
+}
+
+impl<C, R> Spy<C, R>
+where
+    C: Clone,
+    R: Clone,
+{
+    /// Creates a new `Spy` that records arguments but not results.
+    pub fn new() -> Self {
+        Spy {
+            calls: Arc::new(RwLock::new(vec![])),
+            results: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Forward a call to `real`, keeping track of the arguments used.
+    pub fn call<F>(&self, args: C, real: F) -> R
+    where
+        F: FnOnce(C) -> R,
+    {
+        self.calls.write().unwrap().push(args.clone());
+
+        let result = real(args);
+
+        if let Some(ref mut results) = *self.results.write().unwrap() {
+            results.push(result.clone());
+        }
+
+        result
+    }
+
+    /// Also keep track of the results of subsequent calls.
+    pub fn record_results(&self) {
+        let mut results = self.results.write().unwrap();
+
+        if results.is_none() {
+            *results = Some(vec![]);
+        }
+    }
+
+    /// Returns the results of calls made since `Spy::record_results`, in
+    /// order from first to last.
+    pub fn results(&self) -> Vec<R> {
+        self.results.read().unwrap().clone().unwrap_or_default()
+    }
+
+    /// Returns true if `Spy::call` has been called.
+    pub fn called(&self) -> bool {
+        !self.calls.read().unwrap().is_empty()
+    }
+
+    /// Returns the number of times `Spy::call` has been called.
+    pub fn num_calls(&self) -> usize {
+        self.calls.read().unwrap().len()
+    }
+
+    /// Returns the arguments to `Spy::call` in order from first to last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::Spy;
+    ///
+    /// let spy = Spy::<&str, usize>::new();
+    ///
+    /// spy.call("first", str::len);
+    /// spy.call("second", str::len);
+    ///
+    /// assert!(spy.called());
+    /// assert_eq!(spy.num_calls(), 2);
+    /// assert_eq!(spy.calls().as_slice(), ["first", "second"]);
+    /// assert!(spy.results().is_empty());
+    /// ```
+    pub fn calls(&self) -> Vec<C> {
+        self.calls.read().unwrap().clone()
+    }
+
+    /// Reset the call history, including any recorded results, for the `Spy`.
+    pub fn reset_calls(&self) {
+        self.calls.write().unwrap().clear();
+
+        if let Some(ref mut results) = *self.results.write().unwrap() {
+            results.clear()
+        }
+    }
+}
+
+impl<C, R> Default for Spy<C, R>
+where
+    C: Clone,
+    R: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, R> Spy<C, R>
+where
+    C: Clone + PartialEq,
+    R: Clone,
+{
+    /// Returns true if the specified argument has been used for `Spy::call`.
+    pub fn called_with<T: Into<C>>(&self, args: T) -> bool {
+        self.calls.read().unwrap().contains(&args.into())
+    }
+}
+
+impl<C, R> Debug for Spy<C, R>
+where
+    C: Clone + Debug,
+    R: Clone + Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Spy")
+            .field("calls", &self.calls)
+            .field("results", &self.results)
+            .finish()
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use fn_mock::FnMock;
+
+/// Marker for a `StrictMock` that has not yet been given a behavior.
+#[derive(Clone, Copy, Debug)]
+pub enum Unstubbed {}
+
+/// Marker for a `StrictMock` that has been given a behavior.
+#[derive(Clone, Copy, Debug)]
+pub enum Stubbed {}
+
+/// A mock that can only be called once a return value or function has been
+/// configured.
+///
+/// Forgetting to stub a `StrictMock` is a compile-time error rather than a
+/// silently returned default. Once stubbed, a `StrictMock` dereferences to
+/// `FnMock`, which provides `call` and the call history methods.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{StrictMock, Stubbed};
+///
+/// struct MockStore {
+///     get: StrictMock<u64, Option<String>, Stubbed>,
+/// }
+///
+/// let store = MockStore {
+///     get: StrictMock::new().return_value(Some("value".to_string())),
+/// };
+///
+/// assert_eq!(store.get.call(1), Some("value".to_string()));
+/// assert!(store.get.called_with(1u64));
+/// ```
+///
+/// Calling an unstubbed mock does not compile:
+///
+/// ```compile_fail
+/// use pseudo::StrictMock;
+///
+/// let mock = StrictMock::<u64, u64>::new();
+/// mock.call(1);
+/// ```
+pub struct StrictMock<C, R, S = Unstubbed>
+where
+    C: Clone,
+{
+    mock: FnMock<C, R>,
+    state: PhantomData<S>,
+}
+
+impl<C, R> StrictMock<C, R, Unstubbed>
+where
+    C: Clone,
+{
+    /// Creates a new, unstubbed `StrictMock`.
+    pub fn new() -> Self {
+        StrictMock {
+            mock: FnMock::new(|_| unreachable!("unstubbed StrictMock was called")),
+            state: PhantomData,
+        }
+    }
+
+    /// Stub the `StrictMock` with a function, as with `FnMock::use_fn`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::StrictMock;
+    ///
+    /// let mock = StrictMock::<i64, i64>::new().use_fn(|x| x + 1);
+    ///
+    /// assert_eq!(mock.call(1), 2);
+    /// ```
+    pub fn use_fn(self, mock_fn: fn(C) -> R) -> StrictMock<C, R, Stubbed> {
+        self.mock.use_fn(mock_fn);
+        self.stubbed()
+    }
+
+    /// Stub the `StrictMock` with a closure, as with `FnMock::use_closure`.
+    pub fn use_closure(
+        self,
+        mock_fn: Box<dyn Fn(C) -> R + Send + Sync>,
+    ) -> StrictMock<C, R, Stubbed> {
+        self.mock.use_closure(mock_fn);
+        self.stubbed()
+    }
+
+    fn stubbed(self) -> StrictMock<C, R, Stubbed> {
+        StrictMock {
+            mock: self.mock,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<C, R> StrictMock<C, R, Unstubbed>
+where
+    C: Clone,
+    R: Clone + Send + Sync + 'static,
+{
+    /// Stub the `StrictMock` to return `return_value`.
+    pub fn return_value<T: Into<R>>(self, return_value: T) -> StrictMock<C, R, Stubbed> {
+        let return_value = return_value.into();
+        self.use_closure(Box::new(move |_| return_value.clone()))
+    }
+}
+
+impl<C, R> StrictMock<C, R, Stubbed>
+where
+    C: Clone,
+    R: Clone + Send + Sync + 'static,
+{
+    /// Override the configured behavior with `return_value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::StrictMock;
+    ///
+    /// let mock = StrictMock::<(), &str>::new().return_value("first");
+    /// mock.return_value("second");
+    ///
+    /// assert_eq!(mock.call(()), "second");
+    /// ```
+    pub fn return_value<T: Into<R>>(&self, return_value: T) {
+        let return_value = return_value.into();
+        self.mock
+            .use_closure(Box::new(move |_| return_value.clone()))
+    }
+}
+
+impl<C, R> Default for StrictMock<C, R, Unstubbed>
+where
+    C: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, R> Deref for StrictMock<C, R, Stubbed>
+where
+    C: Clone,
+{
+    type Target = FnMock<C, R>;
+
+    fn deref(&self) -> &FnMock<C, R> {
+        &self.mock
+    }
+}
+
+impl<C, R, S> Clone for StrictMock<C, R, S>
+where
+    C: Clone,
+{
+    fn clone(&self) -> Self {
+        StrictMock {
+            mock: self.mock.clone(),
+            state: PhantomData,
+        }
+    }
+}
+
+impl<C, R, S> Debug for StrictMock<C, R, S>
+where
+    C: Clone + Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("StrictMock")
+            .field("mock", &self.mock)
+            .finish()
+    }
+}
@@ -0,0 +1,116 @@
+//! Helpers for table-driven tests.
+//!
+//! A `MockCase` describes one row of a table: what the mock should return and
+//! which interactions are expected. Since it is a plain value, it can be
+//! passed through the row macros of crates such as `test-case` or `rstest`.
+//!
+//! # Examples
+//!
+//! ```
+//! use pseudo::Mock;
+//! use pseudo::table::MockCase;
+//!
+//! fn fetch_twice(fetch: &Mock<u64, Option<&'static str>>, id: u64) -> usize {
+//!     fetch.call(id);
+//!     fetch.call(id).map(str::len).unwrap_or(0)
+//! }
+//!
+//! let cases = vec![
+//!     (1, MockCase::returning(Some("one")).expect_called_with_exactly(vec![1, 1]), 3),
+//!     (2, MockCase::returning(None).expect_calls(2), 0),
+//! ];
+//!
+//! for (id, case, expected) in cases {
+//!     let len = case.run(|fetch| fetch_twice(fetch, id));
+//!     assert_eq!(len, expected);
+//! }
+//! ```
+
+use std::fmt::Debug;
+
+use mock::Mock;
+
+/// A declarative description of a `Mock` for a single test case.
+#[derive(Clone, Debug)]
+pub struct MockCase<C, R> {
+    return_value: R,
+    expected_calls: Option<Vec<C>>,
+    expected_num_calls: Option<usize>,
+}
+
+impl<C, R> MockCase<C, R>
+where
+    C: Clone + PartialEq + Debug,
+    R: Clone,
+{
+    /// Creates a case whose mock returns `return_value`.
+    pub fn returning<T: Into<R>>(return_value: T) -> Self {
+        MockCase {
+            return_value: return_value.into(),
+            expected_calls: None,
+            expected_num_calls: None,
+        }
+    }
+
+    /// Expect the mock to be called with exactly `calls`, in order.
+    pub fn expect_called_with_exactly(mut self, calls: Vec<C>) -> Self {
+        self.expected_calls = Some(calls);
+        self
+    }
+
+    /// Expect the mock to be called exactly `num_calls` times.
+    pub fn expect_calls(mut self, num_calls: usize) -> Self {
+        self.expected_num_calls = Some(num_calls);
+        self
+    }
+
+    /// Creates the `Mock` described by this case.
+    pub fn mock(&self) -> Mock<C, R> {
+        Mock::new(self.return_value.clone())
+    }
+
+    /// Asserts that `mock` satisfies this case's expectations.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use pseudo::table::MockCase;
+    ///
+    /// let case = MockCase::<&str, ()>::returning(())
+    ///     .expect_called_with_exactly(vec!["a"]);
+    /// let mock = case.mock();
+    ///
+    /// mock.call("b");
+    ///
+    /// case.assert(&mock);
+    /// ```
+    pub fn assert(&self, mock: &Mock<C, R>) {
+        if let Some(ref expected_calls) = self.expected_calls {
+            assert_eq!(
+                &mock.calls(),
+                expected_calls,
+                "mock was not called with the expected arguments"
+            );
+        }
+
+        if let Some(expected_num_calls) = self.expected_num_calls {
+            assert_eq!(
+                mock.num_calls(),
+                expected_num_calls,
+                "mock was not called the expected number of times"
+            );
+        }
+    }
+
+    /// Creates the `Mock` described by this case, passes it to `f` and then
+    /// asserts the case's expectations, returning the output of `f`.
+    pub fn run<F, T>(self, f: F) -> T
+    where
+        F: FnOnce(&Mock<C, R>) -> T,
+    {
+        let mock = self.mock();
+        let output = f(&mock);
+        self.assert(&mock);
+        output
+    }
+}
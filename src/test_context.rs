@@ -0,0 +1,148 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::RwLock;
+use std::thread;
+
+type Fixtures = HashMap<TypeId, Box<dyn Any + Send + Sync>>;
+type Check = Box<dyn Fn(&Fixtures) + Send + Sync>;
+
+/// Owns the mocks used by a test, constructing each one on first use.
+///
+/// Mocks are looked up by type, so a test and the code it sets up can both
+/// retrieve the same mock without threading it through by hand. Since clones
+/// of a `Mock` share their state, `TestContext::get` returns a clone.
+///
+/// Checks registered with `TestContext::verify_on_drop` run when the context
+/// is dropped at the end of the test, unless the test is already panicking.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::{Mock, TestContext};
+///
+/// #[derive(Clone, Default)]
+/// struct MockRepo {
+///     save: Mock<String, ()>,
+/// }
+///
+/// fn save_all(repo: &MockRepo, names: &[&str]) {
+///     for name in names {
+///         repo.save.call(name.to_string());
+///     }
+/// }
+///
+/// let ctx = TestContext::new();
+/// # #[cfg(not(feature = "disabled"))]
+/// ctx.verify_on_drop(|repo: &MockRepo| assert_eq!(repo.save.num_calls(), 2));
+///
+/// save_all(&ctx.get::<MockRepo>(), &["a", "b"]);
+///
+/// # #[cfg(not(feature = "disabled"))]
+/// assert!(ctx.get::<MockRepo>().save.called_with("a"));
+/// ```
+#[derive(Default)]
+pub struct TestContext {
+    fixtures: RwLock<Fixtures>,
+    checks: RwLock<Vec<Check>>,
+}
+
+impl TestContext {
+    /// Creates a new, empty `TestContext`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the fixture of type `T`, constructing it with `T::default()`
+    /// if it hasn't been used yet.
+    pub fn get<T>(&self) -> T
+    where
+        T: Any + Clone + Default + Send + Sync,
+    {
+        let mut fixtures = self.fixtures.write().unwrap();
+        let fixture = fixtures
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(T::default()));
+
+        fixture.downcast_ref::<T>().unwrap().clone()
+    }
+
+    /// Use `fixture` as the fixture of type `T`, replacing any existing one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::{Mock, TestContext};
+    ///
+    /// let ctx = TestContext::new();
+    /// ctx.insert(Mock::<u64, Option<&str>>::new(Some("found")));
+    ///
+    /// assert_eq!(ctx.get::<Mock<u64, Option<&str>>>().call(1), Some("found"));
+    /// ```
+    pub fn insert<T>(&self, fixture: T)
+    where
+        T: Any + Send + Sync,
+    {
+        self.fixtures
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Box::new(fixture));
+    }
+
+    /// Run `check` against the fixture of type `T` when the `TestContext` is
+    /// dropped, if that fixture has been used.
+    pub fn verify_on_drop<T>(&self, check: fn(&T))
+    where
+        T: Any,
+    {
+        self.checks.write().unwrap().push(Box::new(move |fixtures| {
+            if let Some(fixture) = fixtures.get(&TypeId::of::<T>()) {
+                check(fixture.downcast_ref::<T>().unwrap())
+            }
+        }))
+    }
+
+    /// Drop every fixture, so that subsequent calls to `TestContext::get`
+    /// construct fresh ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pseudo::{Mock, TestContext};
+    ///
+    /// let ctx = TestContext::new();
+    /// ctx.get::<Mock<(), ()>>().call(());
+    ///
+    /// ctx.reset();
+    ///
+    /// # #[cfg(not(feature = "disabled"))]
+    /// assert!(!ctx.get::<Mock<(), ()>>().called());
+    /// ```
+    pub fn reset(&self) {
+        self.fixtures.write().unwrap().clear()
+    }
+}
+
+impl Drop for TestContext {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            return;
+        }
+
+        let fixtures = self.fixtures.read().unwrap();
+
+        for check in self.checks.read().unwrap().iter() {
+            check(&fixtures)
+        }
+    }
+}
+
+impl Debug for TestContext {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TestContext")
+            .field("num_fixtures", &self.fixtures.read().unwrap().len())
+            .field("num_checks", &self.checks.read().unwrap().len())
+            .finish()
+    }
+}
@@ -0,0 +1,55 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// A failed verification of a `Mock`'s calls, returned by the `verify_*`
+/// methods that correspond to each `assert_*` method.
+///
+/// The fields describe the failure in a form that custom test harnesses and
+/// tooling can inspect, while `Display` gives the same message that the
+/// `assert_*` method panics with.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::Mock;
+///
+/// let mock = Mock::<u64, ()>::default();
+/// mock.set_name("fetch");
+/// mock.call(1);
+///
+/// let err = mock.verify_called_with_all(&[1, 2]).unwrap_err();
+///
+/// assert_eq!(err.mock_name, Some("fetch".to_string()));
+/// assert_eq!(err.expected, "[2]");
+/// assert_eq!(err.actual, vec!["1".to_string()]);
+/// assert_eq!(err.num_calls, 1);
+/// assert_eq!(
+///     err.to_string(),
+///     "fetch: mock was never called with [2]; its calls were [1]"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationError {
+    /// The name of the `Mock`, if it was given one with `Mock::set_name`.
+    pub mock_name: Option<String>,
+    /// A `Debug` representation of the expected interactions.
+    pub expected: String,
+    /// The `Debug` representation of each recorded call, in order.
+    pub actual: Vec<String>,
+    /// The total number of calls, including any that were not recorded.
+    pub num_calls: usize,
+    /// A human-readable description of the failure.
+    pub message: String,
+}
+
+impl Display for VerificationError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.mock_name {
+            Some(ref name) => write!(f, "{}: {}", name, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl Error for VerificationError {}
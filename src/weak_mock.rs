@@ -0,0 +1,96 @@
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{RwLock, Weak};
+
+use arg_record::ArgRecord;
+
+/// A handle for observing a `Mock`'s call history without keeping it alive.
+///
+/// Created by `Mock::downgrade`. Once the `Mock` and all of its clones have
+/// been dropped, every method returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::Mock;
+///
+/// let mock = Mock::<i64, ()>::default();
+/// let weak = mock.downgrade();
+///
+/// mock.call(1);
+///
+/// assert_eq!(weak.num_calls(), Some(1));
+/// assert_eq!(weak.calls(), Some(vec![1]));
+///
+/// drop(mock);
+///
+/// assert_eq!(weak.num_calls(), None);
+/// assert_eq!(weak.calls(), None);
+/// ```
+pub struct WeakMock<C, A>
+where
+    A: ArgRecord<C>,
+{
+    calls: Weak<RwLock<Vec<A::Record>>>,
+    num_calls: Weak<AtomicUsize>,
+}
+
+impl<C, A> WeakMock<C, A>
+where
+    A: ArgRecord<C>,
+{
+    pub(crate) fn new(calls: Weak<RwLock<Vec<A::Record>>>, num_calls: Weak<AtomicUsize>) -> Self {
+        WeakMock { calls, num_calls }
+    }
+
+    /// Returns true if the `Mock` has been dropped.
+    pub fn is_dropped(&self) -> bool {
+        self.num_calls.upgrade().is_none()
+    }
+
+    /// Returns whether the `Mock` has been called, if it is still alive.
+    pub fn called(&self) -> Option<bool> {
+        self.num_calls().map(|num_calls| num_calls > 0)
+    }
+
+    /// Returns the number of times the `Mock` has been called, if it is still
+    /// alive.
+    pub fn num_calls(&self) -> Option<usize> {
+        self.num_calls
+            .upgrade()
+            .map(|num_calls| num_calls.load(Ordering::SeqCst))
+    }
+
+    /// Returns the arguments the `Mock` has been called with, if it is still
+    /// alive.
+    pub fn calls(&self) -> Option<Vec<A::Record>> {
+        self.calls
+            .upgrade()
+            .map(|calls| calls.read().unwrap().clone())
+    }
+}
+
+impl<C, A> Clone for WeakMock<C, A>
+where
+    A: ArgRecord<C>,
+{
+    fn clone(&self) -> Self {
+        WeakMock {
+            calls: self.calls.clone(),
+            num_calls: self.num_calls.clone(),
+        }
+    }
+}
+
+impl<C, A> Debug for WeakMock<C, A>
+where
+    A: ArgRecord<C>,
+    A::Record: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("WeakMock")
+            .field("calls", &self.calls())
+            .finish()
+    }
+}
@@ -0,0 +1,48 @@
+use arg_record::ArgRecord;
+use mock::{Matcher, Mock};
+
+/// A conditional behavior being added to a `Mock`, returned by `Mock::when`.
+///
+/// Conditional behaviors are evaluated in the order they were added, and the
+/// first one whose predicate matches the arguments wins. If none match, the
+/// `Mock` falls back to its other behavior.
+///
+/// # Examples
+///
+/// ```
+/// use pseudo::Mock;
+///
+/// let price = Mock::<(&str, u64), u64>::default();
+/// price.when(|&(_, qty)| qty == 0).then_return(0);
+/// price.when(|&(sku, _)| sku.starts_with("gift-")).then_return(5);
+/// price.when(|&(_, qty)| qty >= 100).then_return(80);
+///
+/// assert_eq!(price.call(("gift-card", 0)), 0);
+/// assert_eq!(price.call(("gift-card", 200)), 5);
+/// assert_eq!(price.call(("widget", 200)), 80);
+/// assert_eq!(price.call(("widget", 1)), 0);
+/// ```
+#[must_use = "`When` does nothing until `When::then_return` is called"]
+pub struct When<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    mock: &'a Mock<C, R, A>,
+    predicate: Matcher<C>,
+}
+
+impl<'a, C, R, A> When<'a, C, R, A>
+where
+    R: Clone,
+    A: ArgRecord<C>,
+{
+    pub(crate) fn new(mock: &'a Mock<C, R, A>, predicate: Matcher<C>) -> Self {
+        When { mock, predicate }
+    }
+
+    /// Return `return_value` when the predicate matches.
+    pub fn then_return(self, return_value: R) {
+        self.mock.add_stub(self.predicate, return_value)
+    }
+}